@@ -2,9 +2,22 @@ use std::env::current_dir;
 use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cw20::{AllAccountsResponse, BalanceResponse, TokenInfoResponse};
 
-use sei_token::msg::{ExecuteMsg, GetCountResponse, InstantiateMsg, QueryMsg};
-use sei_token::state::State;
+use sei_token::events::{
+    CampaignContributionEvent, CampaignRefundEvent, ClaimMintedEvent, ClaimRedeemedEvent,
+    EscrowCreatedEvent, JointPotDepositEvent, JointPotWithdrawEvent, OwnerChangedEvent,
+    PotDepositEvent, PotRefundEvent, PotRejectedEvent, PotTransferEvent, PotWithdrawEvent,
+    PotWithdrawFailedEvent,
+};
+use sei_token::msg::{
+    AccountResponse, CampaignResponse, ClaimTokenResponse, ConfigResponse, ContractInfoResponse,
+    ContributionResponse, DepositsResponse, EscrowResponse, ExecuteMsg, ForwardResponse,
+    InstantiateMsg, JointPotResponse, JointProposalResponse, MilestonesResponse, PotReceiveMsg,
+    PotResponse, PotsResponse, QueryMsg, ReceiveMsg, RoleMembersResponse, RolesResponse,
+    SolvencyResponse, SplitTemplatesResponse, StatementResponse, StatsResponse,
+};
+use sei_token::state::{AcceptPolicy, Config, PauseState, Pot};
 
 fn main() {
     let mut out_dir = current_dir().unwrap();
@@ -15,6 +28,47 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(State), &out_dir);
-    export_schema(&schema_for!(GetCountResponse), &out_dir);
+    export_schema(&schema_for!(PotReceiveMsg), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(Config), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(ContractInfoResponse), &out_dir);
+    export_schema(&schema_for!(Pot), &out_dir);
+    export_schema(&schema_for!(PauseState), &out_dir);
+    export_schema(&schema_for!(AcceptPolicy), &out_dir);
+    export_schema(&schema_for!(PotResponse), &out_dir);
+    export_schema(&schema_for!(PotsResponse), &out_dir);
+    export_schema(&schema_for!(EscrowResponse), &out_dir);
+    export_schema(&schema_for!(MilestonesResponse), &out_dir);
+    export_schema(&schema_for!(ForwardResponse), &out_dir);
+    export_schema(&schema_for!(SplitTemplatesResponse), &out_dir);
+    export_schema(&schema_for!(CampaignResponse), &out_dir);
+    export_schema(&schema_for!(ContributionResponse), &out_dir);
+    export_schema(&schema_for!(ClaimTokenResponse), &out_dir);
+    export_schema(&schema_for!(JointPotResponse), &out_dir);
+    export_schema(&schema_for!(JointProposalResponse), &out_dir);
+    export_schema(&schema_for!(AccountResponse), &out_dir);
+    export_schema(&schema_for!(RolesResponse), &out_dir);
+    export_schema(&schema_for!(RoleMembersResponse), &out_dir);
+    export_schema(&schema_for!(DepositsResponse), &out_dir);
+    export_schema(&schema_for!(StatementResponse), &out_dir);
+    export_schema(&schema_for!(SolvencyResponse), &out_dir);
+    export_schema(&schema_for!(StatsResponse), &out_dir);
+    export_schema(&schema_for!(BalanceResponse), &out_dir);
+    export_schema(&schema_for!(TokenInfoResponse), &out_dir);
+    export_schema(&schema_for!(AllAccountsResponse), &out_dir);
+    export_schema(&schema_for!(PotDepositEvent), &out_dir);
+    export_schema(&schema_for!(PotWithdrawEvent), &out_dir);
+    export_schema(&schema_for!(PotWithdrawFailedEvent), &out_dir);
+    export_schema(&schema_for!(EscrowCreatedEvent), &out_dir);
+    export_schema(&schema_for!(PotRefundEvent), &out_dir);
+    export_schema(&schema_for!(PotRejectedEvent), &out_dir);
+    export_schema(&schema_for!(PotTransferEvent), &out_dir);
+    export_schema(&schema_for!(ClaimMintedEvent), &out_dir);
+    export_schema(&schema_for!(ClaimRedeemedEvent), &out_dir);
+    export_schema(&schema_for!(CampaignContributionEvent), &out_dir);
+    export_schema(&schema_for!(CampaignRefundEvent), &out_dir);
+    export_schema(&schema_for!(JointPotDepositEvent), &out_dir);
+    export_schema(&schema_for!(JointPotWithdrawEvent), &out_dir);
+    export_schema(&schema_for!(OwnerChangedEvent), &out_dir);
 }
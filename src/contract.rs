@@ -1,22 +1,77 @@
-use std::ops::{Div, Add};
+use std::ops::Div;
 
 #[cfg(not(feature = "library"))]
-use cosmwasm_std::{from_binary, entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128, Uint64};
-use cw20::{Cw20Contract, Cw20ExecuteMsg, Cw20ReceiveMsg};
-use cw2::set_contract_version;
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{coins, from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, QueryRequest, Reply, Response, StdError, StdResult, Storage, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg, WasmQuery};
+use cosmwasm_std::ContractInfoResponse as WasmContractInfoResponse;
+use cw_storage_plus::Bound;
+use cw20::{
+    AllAccountsResponse, AllowanceResponse, BalanceResponse, Cw20Contract, Cw20ExecuteMsg,
+    Cw20QueryMsg, Cw20ReceiveMsg, Denom, Expiration, MinterResponse,
+};
+use cw2::{get_contract_version, set_contract_version};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg, PotResponse};
-use crate::state::{Config, CONFIG, Pot, POTS, save_pot};
+use crate::events::{
+    CampaignContributionEvent, CampaignRefundEvent, ClaimMintedEvent, ClaimRedeemedEvent,
+    EscrowCreatedEvent, JointPotDepositEvent, JointPotWithdrawEvent, OwnerChange, OwnerChangedEvent,
+    PotDepositEvent, PotRefundEvent, PotRejectedEvent, PotTransferEvent, PotWithdrawEvent,
+    PotWithdrawFailedEvent,
+};
+use crate::msg::{
+    AccountResponse, AssetSolvency, AssetStatsResponse, CampaignResponse, ClaimTokenInstantiateMsg,
+    ClaimTokenResponse, ConfigResponse, ContractInfoResponse, ContributionResponse, DepositInfo,
+    DepositsResponse, EscrowResponse, ExecuteMsg, ForwardResponse, InstantiateMsg, JointPotResponse,
+    JointProposalResponse, MilestoneInfo, MilestoneMsg, MilestonesResponse, PotReceiveMsg,
+    PotResponse, PotsResponse, QueryMsg, ReceiveMsg, RoleMembersResponse, RolesResponse,
+    SolvencyResponse, SplitTemplateInfo, SplitTemplatesResponse, StatementEntry, StatementResponse,
+    StatsResponse, TemplateRef,
+};
+use crate::state::{
+    add_liability, asset_key, credit_pot, debit_pot, deposits, has_role, joint_members,
+    resolve_forward, save_deposit, save_ledger_entry, sub_liability, AcceptPolicy, Campaign,
+    CampaignStatus, Config, Deposit, Escrow, EscrowStatus, Instantiation, JointProposal,
+    LedgerEntry, LedgerKind, Milestone, PauseState, PendingWithdrawal, Pot, RejectFallback, Role,
+    SplitTemplate, TokenView, ACCEPT_POLICIES, ACCOUNTS, ASSETS, BENEFICIARIES, CAMPAIGNS,
+    CAMPAIGN_SEQ, CLAIM_TOKENS, CLAIM_TOKEN_ASSETS, CONFIG, CONTRIBUTIONS, DEFAULT_PROPOSAL_TTL,
+    DEFAULT_TOKEN_DECIMALS, ESCROWS, ESCROW_SEQ, FORWARDS, INSTANTIATION, JOINT_POTS,
+    JOINT_PROPOSALS, LEDGER, LIABILITIES, LOCKED, PAUSE, PENDING_CLAIM_TOKEN, PENDING_FORWARDS,
+    PENDING_WITHDRAWAL, POTS, POT_HOLDERS, POT_TOTALS, PROPOSAL_SEQ, PROPOSAL_TTL, ROLES, STATS,
+    TEMPLATES, TEMPLATE_NAMES, TEMPLATE_SEQ, TOKEN_VIEW, WITHDRAW_ALLOWANCES,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:sei-token";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+// FEATURES lists what this code supports, reported by the ContractInfo query.
+const FEATURES: &[&str] = &[
+    "cw20_deposits",
+    "split_templates",
+    "withdraw_allowances",
+    "escrow",
+    "milestones",
+    "joint_pots",
+    "campaigns",
+    "forwarding",
+    "accept_policies",
+    "internal_transfers",
+    "cw20_queries",
+    "claim_tokens",
+];
+
+// reply ids
+const WITHDRAW_REPLY_ID: u64 = 1;
+const CLAIM_TOKEN_REPLY_ID: u64 = 2;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -27,296 +82,4226 @@ pub fn instantiate(
         .and_then(|addr_string| deps.api.addr_validate(addr_string.as_str()).ok())
         .unwrap_or(info.sender);
 
+    let mut native_denoms: Vec<String> = vec![];
+    for denom in msg.native_denoms {
+        if let Denom::Native(denom) = validate_asset(deps.as_ref(), Denom::Native(denom))? {
+            if !native_denoms.contains(&denom) {
+                native_denoms.push(denom);
+            }
+        }
+    }
+    let mut cw20_tokens: Vec<Addr> = vec![];
+    for addr in &msg.cw20_tokens {
+        let addr = deps.api.addr_validate(addr)?;
+        if !cw20_tokens.contains(&addr) {
+            cw20_tokens.push(addr);
+        }
+    }
+    let primary_asset = native_denoms
+        .first()
+        .map(|denom| Denom::Native(denom.clone()))
+        .or_else(|| cw20_tokens.first().map(|addr| Denom::Cw20(addr.clone())))
+        .ok_or(ContractError::NoAcceptedAsset {})?;
     let config = Config {
         owner: owner.clone(),
+        native_denoms,
+        cw20_tokens,
+        primary_asset,
+    };
+
+    CONFIG.save(deps.storage, &config)?;
+    for denom in &config.native_denoms {
+        ASSETS.save(deps.storage, denom, &Denom::Native(denom.clone()))?;
+    }
+    for addr in &config.cw20_tokens {
+        ASSETS.save(deps.storage, addr.as_str(), &Denom::Cw20(addr.clone()))?;
+    }
+    ROLES.save(deps.storage, (Role::Owner.as_str(), owner.as_str()), &Empty {})?;
+    INSTANTIATION.save(deps.storage, &Instantiation {
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", owner.clone())
+        .add_event(OwnerChangedEvent {
+            owner: owner.into_string(),
+            change: OwnerChange::Added,
+        }.into()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    // Branches that credit pots check the deposits pause, branches that send funds out or back
+    // to a depositor check the withdrawals pause. Administration and settings that move no
+    // balance stay available so a paused contract can still be managed.
+    let pause = PAUSE.may_load(deps.storage)?.unwrap_or_default();
+    match msg {
+        ExecuteMsg::CreatePot {
+            target_addr_1,
+            target_addr_2,
+            template,
+            on_reject,
+            mint_claims,
+            receive_msg,
+        } => {
+            check_deposits_not_paused(&pause)?;
+            execute_create_pot(
+                deps,
+                env,
+                info,
+                target_addr_1,
+                target_addr_2,
+                template,
+                on_reject,
+                mint_claims,
+                receive_msg,
+            )
+        }
+        ExecuteMsg::CreateEscrowPot {
+            target_addr_1,
+            target_addr_2,
+            arbiter,
+            timeout,
+            milestones,
+            receive_msg,
+        } => {
+            check_deposits_not_paused(&pause)?;
+            execute_create_escrow_pot(
+                deps,
+                env,
+                info,
+                [target_addr_1, target_addr_2],
+                arbiter,
+                timeout,
+                milestones,
+                receive_msg,
+            )
+        }
+        ExecuteMsg::CreateCampaign {
+            target_addr_1,
+            target_addr_2,
+            asset,
+            goal,
+            deadline,
+        } => execute_create_campaign(deps, env, info, [target_addr_1, target_addr_2], asset, goal, deadline),
+        ExecuteMsg::Contribute { id, receive_msg } => {
+            check_deposits_not_paused(&pause)?;
+            execute_contribute(deps, env, info, id, receive_msg)
+        }
+        ExecuteMsg::Finalize { id } => {
+            check_deposits_not_paused(&pause)?;
+            execute_finalize(deps, env, id)
+        }
+        ExecuteMsg::ClaimRefund { id } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_claim_refund(deps, env, info, id)
+        }
+        ExecuteMsg::CreateJointPot {
+            target_addr_1,
+            target_addr_2,
+            receive_msg,
+        } => {
+            check_deposits_not_paused(&pause)?;
+            execute_create_joint_pot(deps, env, info, target_addr_1, target_addr_2, receive_msg)
+        }
+        ExecuteMsg::ProposeJointWithdrawal {
+            partner,
+            recipient,
+            amount,
+            asset,
+        } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_propose_joint_withdrawal(deps, env, info, partner, recipient, amount, asset)
+        }
+        ExecuteMsg::ConfirmJointWithdrawal { id } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_confirm_joint_withdrawal(deps, env, info, id)
+        }
+        ExecuteMsg::Release { id } => {
+            check_deposits_not_paused(&pause)?;
+            execute_release(deps, env, info, id)
+        }
+        ExecuteMsg::CompleteMilestone { id, milestone } => {
+            check_deposits_not_paused(&pause)?;
+            execute_complete_milestone(deps, env, info, id, milestone)
+        }
+        ExecuteMsg::Refund { id } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_refund(deps, env, info, id)
+        }
+        ExecuteMsg::WithdrawPot {
+            amount,
+            asset,
+        } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_withdraw_pot(deps, env, info, amount, asset)
+        }
+        ExecuteMsg::IncreaseWithdrawAllowance {
+            spender,
+            amount,
+            expires,
+            asset,
+        } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_increase_withdraw_allowance(deps, env, info, spender, amount, expires, asset)
+        }
+        ExecuteMsg::DecreaseWithdrawAllowance {
+            spender,
+            amount,
+            expires,
+            asset,
+        } => {
+            // Lowering an allowance only reduces risk, so it stays available while paused.
+            execute_decrease_withdraw_allowance(deps, env, info, spender, amount, expires, asset)
+        }
+        ExecuteMsg::WithdrawFrom {
+            owner,
+            recipient,
+            amount,
+            asset,
+        } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_withdraw_from(deps, env, info, owner, recipient, amount, asset)
+        }
+        // Admin messages stay available while paused so the contract can be unpaused.
+        ExecuteMsg::GrantRole { role, addr } => execute_grant_role(deps, info, role, addr),
+        ExecuteMsg::RevokeRole { role, addr } => execute_revoke_role(deps, info, role, addr),
+        ExecuteMsg::SetPause {
+            deposits,
+            withdrawals,
+        } => execute_set_pause(deps, info, deposits, withdrawals),
+        // Sweeping sends funds out of the contract, so it waits for withdrawals like users do.
+        ExecuteMsg::SweepSurplus { asset, recipient } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_sweep_surplus(deps, env, info, asset, recipient)
+        }
+        ExecuteMsg::SetAcceptPolicy { policy } => execute_set_accept_policy(deps, info, policy),
+        ExecuteMsg::SetForward { to } => execute_set_forward(deps, info, to),
+        ExecuteMsg::ConfirmForward { from } => execute_confirm_forward(deps, info, from),
+        ExecuteMsg::RemoveForward {} => execute_remove_forward(deps, info),
+        ExecuteMsg::MoveBalance { asset } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_move_balance(deps, env, info, asset)
+        }
+        ExecuteMsg::TransferBalance { to, amount, asset } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_transfer_balance(deps, env, info, to, amount, asset)
+        }
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            asset,
+            msg,
+        } => {
+            check_withdrawals_not_paused(&pause)?;
+            execute_send(deps, env, info, contract, amount, asset, msg)
+        }
+        ExecuteMsg::SaveSplitTemplate {
+            name,
+            recipients,
+            weights,
+            immutable,
+        } => execute_save_split_template(deps, info, name, recipients, weights, immutable),
+        ExecuteMsg::DeleteSplitTemplate { name } => execute_delete_split_template(deps, info, name),
+        ExecuteMsg::SetProposalTtl { seconds } => execute_set_proposal_ttl(deps, info, seconds),
+        ExecuteMsg::CreateClaimToken {
+            asset,
+            code_id,
+            name,
+            symbol,
+            decimals,
+        } => execute_create_claim_token(deps, env, info, asset, code_id, name, symbol, decimals),
+        ExecuteMsg::Receive(wrapped) => execute_receive(deps, env, info, &pause, wrapped),
+        ExecuteMsg::SetTokenView {
+            asset,
+            name,
+            symbol,
+            decimals,
+        } => execute_set_token_view(deps, info, asset, name, symbol, decimals),
+        ExecuteMsg::AddAcceptedAsset { asset } => execute_add_accepted_asset(deps, info, asset),
+        ExecuteMsg::RemoveAcceptedAsset { asset } => {
+            execute_remove_accepted_asset(deps, info, asset)
+        }
+    }
+}
+
+fn check_deposits_not_paused(pause: &PauseState) -> Result<(), ContractError> {
+    if pause.deposits {
+        return Err(ContractError::Paused { scope: "deposits".to_string() });
+    }
+    Ok(())
+}
+
+fn check_withdrawals_not_paused(pause: &PauseState) -> Result<(), ContractError> {
+    if pause.withdrawals {
+        return Err(ContractError::Paused { scope: "withdrawals".to_string() });
+    }
+    Ok(())
+}
+
+/// ensure_any_role fails with Unauthorized unless addr holds one of roles.
+fn ensure_any_role(storage: &dyn Storage, addr: &Addr, roles: &[Role]) -> Result<(), ContractError> {
+    if roles.iter().any(|role| has_role(storage, addr, *role)) {
+        Ok(())
+    } else {
+        Err(ContractError::Unauthorized {})
+    }
+}
+
+pub fn execute_grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner])?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+    let newly_granted = !ROLES.has(deps.storage, (role.as_str(), addr.as_str()));
+    ROLES.save(deps.storage, (role.as_str(), addr.as_str()), &Empty {})?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("addr", addr.clone());
+    if role == Role::Owner && newly_granted {
+        res = res.add_event(OwnerChangedEvent {
+            owner: addr.into_string(),
+            change: OwnerChange::Added,
+        }.into());
+    }
+    Ok(res)
+}
+
+pub fn execute_revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    addr: String,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner])?;
+
+    let addr = deps.api.addr_validate(&addr)?;
+
+    // Never leave the contract without anyone able to grant roles.
+    if role == Role::Owner
+        && ROLES
+            .prefix(Role::Owner.as_str())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .all(|owner| matches!(owner, Ok(owner) if owner == addr.as_str()))
+    {
+        return Err(ContractError::LastOwner {});
+    }
+    let was_granted = ROLES.has(deps.storage, (role.as_str(), addr.as_str()));
+    ROLES.remove(deps.storage, (role.as_str(), addr.as_str()));
+
+    // Config.owner must name a current owner, so hand it to one of the remaining owners.
+    let mut config = CONFIG.load(deps.storage)?;
+    if role == Role::Owner && config.owner == addr {
+        let owner = ROLES
+            .prefix(Role::Owner.as_str())
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .ok_or(ContractError::LastOwner {})??;
+        config.owner = Addr::unchecked(owner);
+        CONFIG.save(deps.storage, &config)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("role", role.as_str())
+        .add_attribute("addr", addr.clone());
+    if role == Role::Owner && was_granted {
+        res = res.add_event(OwnerChangedEvent {
+            owner: addr.into_string(),
+            change: OwnerChange::Removed,
+        }.into());
+    }
+    Ok(res)
+}
+
+pub fn execute_set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposits: bool,
+    withdrawals: bool,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Pauser])?;
+
+    let pause = PauseState {
+        deposits,
+        withdrawals,
+        set_by: Some(info.sender.clone()),
+    };
+    PAUSE.save(deps.storage, &pause)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("sender", info.sender)
+        .add_attribute("deposits", deposits.to_string())
+        .add_attribute("withdrawals", withdrawals.to_string()))
+}
+
+pub fn execute_withdraw_pot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    // address that requested the withdrawl
+    let address_request = info.sender;
+    let asset = resolve_asset(deps.as_ref(), asset)?;
+    let (msg, event) =
+        withdraw_from_pot(deps, &env, &address_request, &address_request, None, &asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("address", address_request.to_string())
+        .add_attribute("asset", asset_key(&asset))
+        .add_submessage(msg)
+        .add_event(event.into()))
+}
+
+pub fn execute_withdraw_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let recipient_addr = deps.api.addr_validate(&recipient)?;
+    let asset = resolve_asset(deps.as_ref(), asset)?;
+
+    deduct_withdraw_allowance(deps.storage, &owner_addr, &info.sender, &asset_key(&asset), &env, amount)?;
+    let (msg, event) = withdraw_from_pot(
+        deps,
+        &env,
+        &owner_addr,
+        &recipient_addr,
+        Some(&info.sender),
+        &asset,
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_from")
+        .add_attribute("owner", owner_addr)
+        .add_attribute("spender", info.sender)
+        .add_attribute("recipient", recipient_addr)
+        .add_attribute("amount", amount)
+        .add_attribute("asset", asset_key(&asset))
+        .add_submessage(msg)
+        .add_event(event.into()))
+}
+
+pub fn execute_sweep_surplus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Denom,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner])?;
+
+    let asset = validate_asset(deps.as_ref(), asset)?;
+    let key = asset_key(&asset);
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let balance = query_asset_balance(deps.as_ref(), &env, &asset)?;
+    let liabilities = LIABILITIES.may_load(deps.storage, &key)?.unwrap_or_default();
+    // Beneficiaries' funds are never swept, only what sits above them.
+    let surplus = balance.saturating_sub(liabilities);
+    if surplus.is_zero() {
+        return Err(ContractError::NoSurplus {});
+    }
+
+    let msg = transfer_msg(&asset, &recipient, surplus)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sweep_surplus")
+        .add_attribute("asset", key)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", surplus)
+        .add_message(msg))
+}
+
+pub fn execute_add_accepted_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Denom,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Operator])?;
+
+    let asset = validate_asset(deps.as_ref(), asset)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    match &asset {
+        Denom::Native(denom) if !config.native_denoms.contains(denom) => {
+            config.native_denoms.push(denom.clone())
+        }
+        Denom::Cw20(addr) if !config.cw20_tokens.contains(addr) => {
+            config.cw20_tokens.push(addr.clone())
+        }
+        _ => {}
+    }
+    CONFIG.save(deps.storage, &config)?;
+    ASSETS.save(deps.storage, &asset_key(&asset), &asset)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_accepted_asset")
+        .add_attribute("asset", asset_key(&asset)))
+}
+
+pub fn execute_remove_accepted_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Denom,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Operator])?;
+
+    let asset = validate_asset(deps.as_ref(), asset)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    // The primary asset stays accepted, so the contract never runs out of accepted assets.
+    if asset == config.primary_asset {
+        return Err(ContractError::PrimaryAsset { asset: asset_key(&asset) });
+    }
+    match &asset {
+        Denom::Native(denom) => config.native_denoms.retain(|d| d != denom),
+        Denom::Cw20(addr) => config.cw20_tokens.retain(|a| a != addr),
+    }
+    // ASSETS keeps the entry so existing balances can still be withdrawn.
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_accepted_asset")
+        .add_attribute("asset", asset_key(&asset)))
+}
+
+/// validate_asset checks the address of a cw20 asset.
+fn validate_asset(deps: Deps, asset: Denom) -> StdResult<Denom> {
+    match asset {
+        Denom::Native(denom) if denom.is_empty() => Err(StdError::generic_err("Denom cannot be empty")),
+        Denom::Native(denom) => Ok(Denom::Native(denom)),
+        Denom::Cw20(addr) => Ok(Denom::Cw20(deps.api.addr_validate(addr.as_str())?)),
+    }
+}
+
+/// resolve_asset looks up an asset that was accepted at some point, falling back to the
+/// primary asset when none is given.
+fn resolve_asset(deps: Deps, asset: Option<String>) -> StdResult<Denom> {
+    match asset {
+        Some(key) => ASSETS.load(deps.storage, &key),
+        None => Ok(CONFIG.load(deps.storage)?.primary_asset),
+    }
+}
+
+/// transfer_msg pays amount of asset to recipient.
+fn transfer_msg(asset: &Denom, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    match asset {
+        Denom::Native(denom) => Ok(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(amount.u128(), denom),
+        }
+        .into()),
+        Denom::Cw20(addr) => Cw20Contract(addr.clone()).call(Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        }),
+    }
+}
+
+/// withdraw_from_pot debits amount from owner's pot and returns the transfer paying it to recipient,
+/// together with the pot_withdraw event describing it. Transfers to contracts are sent with
+/// reply_on_error so a rejected transfer is credited back in `reply` instead of failing the call.
+/// Every withdrawal path goes through here so the balance checks stay identical.
+fn withdraw_from_pot(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    recipient: &Addr,
+    spender: Option<&Addr>,
+    asset: &Denom,
+    amount: Uint128,
+) -> Result<(SubMsg, PotWithdrawEvent), ContractError> {
+    let key = asset_key(asset);
+    // Find the address in POTS. Error if not found.
+    let p = POTS.load(deps.storage, (owner.as_str(), &key))?;
+    // Verify the amount is correct
+    if amount > p.collected {
+        return Err(ContractError::CustomError { val:"Wrong amount to withdraw".to_string() });
+    }
+    // Making sure owner is equal to target address in pot.
+    if owner != &p.target_addr {
+        // This check is quite useless
+        return Err(ContractError::CustomError { val:"Wrong address to withdraw".to_string() });
+    }
+
+    let msg = transfer_msg(asset, recipient, amount)?;
+    let msg = if is_contract(deps.as_ref(), recipient) {
+        PENDING_WITHDRAWAL.save(deps.storage, &PendingWithdrawal {
+            owner: owner.clone(),
+            recipient: recipient.clone(),
+            spender: spender.cloned(),
+            asset: key.clone(),
+            amount,
+        })?;
+        SubMsg::reply_always(msg, WITHDRAW_REPLY_ID)
+    } else {
+        SubMsg::new(msg)
+    };
+
+    let p = debit_pot(deps.storage, owner, &key, amount)?;
+
+    sub_liability(deps.storage, &key, amount)?;
+    ACCOUNTS.update(deps.storage, (owner.as_str(), &key), |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.withdrawn += amount;
+        Ok(totals)
+    })?;
+    STATS.update(deps.storage, &key, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.withdrawn += amount;
+        Ok(stats)
+    })?;
+    save_ledger_entry(deps.storage, owner, &LedgerEntry {
+        kind: LedgerKind::Withdrawal,
+        counterparty: recipient.clone(),
+        amount,
+        balance: p.collected,
+        asset: key.clone(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+    let event = PotWithdrawEvent {
+        owner: owner.to_string(),
+        recipient: recipient.to_string(),
+        spender: spender.map(Addr::to_string),
+        asset: key,
+        amount,
+        balance: p.collected,
+    };
+    Ok((msg, event))
+}
+
+/// is_contract tells whether addr belongs to a contract rather than an account.
+fn is_contract(deps: Deps, addr: &Addr) -> bool {
+    let query = QueryRequest::Wasm(WasmQuery::ContractInfo { contract_addr: addr.to_string() });
+    deps.querier.query::<WasmContractInfoResponse>(&query).is_ok()
+}
+
+pub fn execute_increase_withdraw_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+
+    WITHDRAW_ALLOWANCES.update(
+        deps.storage,
+        (info.sender.as_str(), spender_addr.as_str(), &asset),
+        |allow| -> Result<_, ContractError> {
+            let mut val = allow.unwrap_or_default();
+            if let Some(exp) = expires {
+                if exp.is_expired(&env.block) {
+                    return Err(ContractError::InvalidExpiration {});
+                }
+                val.expires = exp;
+            }
+            val.allowance += amount;
+            Ok(val)
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "increase_withdraw_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount)
+        .add_attribute("asset", asset))
+}
+
+pub fn execute_decrease_withdraw_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount: Uint128,
+    expires: Option<Expiration>,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+
+    let key = (info.sender.as_str(), spender_addr.as_str(), asset.as_str());
+    let mut allowance = WITHDRAW_ALLOWANCES.load(deps.storage, key)?;
+    if amount < allowance.allowance {
+        allowance.allowance -= amount;
+        if let Some(exp) = expires {
+            if exp.is_expired(&env.block) {
+                return Err(ContractError::InvalidExpiration {});
+            }
+            allowance.expires = exp;
+        }
+        WITHDRAW_ALLOWANCES.save(deps.storage, key, &allowance)?;
+    } else {
+        WITHDRAW_ALLOWANCES.remove(deps.storage, key);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "decrease_withdraw_allowance")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount)
+        .add_attribute("asset", asset))
+}
+
+/// deduct_withdraw_allowance lowers the spender's allowance on owner's pot, failing if it
+/// is expired or too small.
+fn deduct_withdraw_allowance(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    asset: &str,
+    env: &Env,
+    amount: Uint128,
+) -> Result<AllowanceResponse, ContractError> {
+    WITHDRAW_ALLOWANCES.update(storage, (owner.as_str(), spender.as_str(), asset), |current| {
+        match current {
+            Some(mut a) => {
+                if a.expires.is_expired(&env.block) {
+                    Err(ContractError::Expired {})
+                } else {
+                    a.allowance = a
+                        .allowance
+                        .checked_sub(amount)
+                        .map_err(|_| ContractError::NoAllowance {})?;
+                    Ok(a)
+                }
+            }
+            None => Err(ContractError::NoAllowance {}),
+        }
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_pot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target_addr_1: Option<String>,
+    target_addr_2: Option<String>,
+    template: Option<TemplateRef>,
+    on_reject: RejectFallback,
+    mint_claims: bool,
+    wrapped: Option<Cw20ReceiveMsg>,
+) -> Result<Response, ContractError> {
+    let (asset, depositor, amount) = received_funds(deps.as_ref(), info, wrapped)?;
+    let claim_token = match mint_claims {
+        true => Some(
+            CLAIM_TOKENS
+                .may_load(deps.storage, &asset)?
+                .ok_or_else(|| ContractError::NoClaimToken { asset: asset.clone() })?,
+        ),
+        false => None,
     };
 
-    CONFIG.save(deps.storage, &config)?;
+    let mut res = Response::new()
+        .add_attribute("action", "execute_create_pot")
+        .add_attribute("asset", asset.clone());
+    let (targets, weights) = match (target_addr_1, target_addr_2, template) {
+        (Some(target_addr_1), Some(target_addr_2), None) => {
+            let targets = vec![
+                deps.api.addr_validate(target_addr_1.as_str())?,
+                deps.api.addr_validate(target_addr_2.as_str())?,
+            ];
+            res = res
+                .add_attribute("target_addr_1", target_addr_1)
+                .add_attribute("target_addr_2", target_addr_2);
+            (targets, vec![1, 1])
+        }
+        (None, None, Some(template)) => {
+            let id = match template {
+                TemplateRef::Id(id) => id,
+                TemplateRef::Name(name) => TEMPLATE_NAMES.load(deps.storage, (depositor.as_str(), &name))?,
+            };
+            let template = TEMPLATES.load(deps.storage, id)?;
+            res = res.add_attribute("template", id.to_string());
+            (template.recipients, template.weights)
+        }
+        _ => return Err(ContractError::CustomError { val:"Give either two targets or a template".to_string() }),
+    };
+
+    // Accept policies are checked against the final recipient before anything is credited.
+    let mut accepted = Vec::with_capacity(targets.len());
+    for target in &targets {
+        let recipient = resolve_forward(deps.storage, target)?;
+        let policy = ACCEPT_POLICIES.may_load(deps.storage, recipient.as_str())?.unwrap_or_default();
+        if !policy.accepts(&depositor) && on_reject == RejectFallback::Fail {
+            return Err(ContractError::Rejected { recipient: recipient.into_string() });
+        }
+        accepted.push((recipient, policy.accepts(&depositor)));
+    }
+
+    // Each target gets its weighted share rounded down; the remainder is not owed to anyone.
+    let total_weight = total_weight(&weights).ok_or(ContractError::InvalidTemplate {})?;
+    let mut credited = Uint128::zero();
+    let mut events = Vec::with_capacity(targets.len());
+    for ((target, weight), (recipient, accepts)) in targets.iter().zip(weights).zip(accepted) {
+        let share = amount.multiply_ratio(weight, total_weight);
+        if let (true, Some(token)) = (accepts, &claim_token) {
+            // cw20 refuses to mint nothing, so a share rounded down to zero is left out like any
+            // other remainder.
+            if !share.is_zero() {
+                let (msg, event) = mint_claim(deps.storage, &env, &asset, &depositor, target, token, share)?;
+                res = res.add_message(msg);
+                events.push(event);
+            }
+        } else if accepts {
+            events.push(credit_deposit(deps.storage, &env, &asset, &depositor, target, share)?);
+        } else {
+            let pot = credit_beneficiary(
+                deps.storage,
+                &env,
+                &asset,
+                LedgerKind::Refund,
+                &recipient,
+                &depositor,
+                share,
+            )?;
+            events.push(PotRejectedEvent {
+                depositor: depositor.to_string(),
+                recipient: recipient.into_string(),
+                asset: asset.clone(),
+                amount: share,
+                balance: pot.collected,
+            }.into());
+        }
+        credited += share;
+    }
+    add_liability(deps.storage, &asset, credited)?;
+    record_deposit_stats(deps.storage, &asset, amount)?;
+
+    Ok(res.add_events(events))
+}
+
+pub fn execute_set_accept_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy: AcceptPolicy,
+) -> Result<Response, ContractError> {
+    if let AcceptPolicy::Allowlist { depositors } = &policy {
+        for depositor in depositors {
+            deps.api.addr_validate(depositor)?;
+        }
+    }
+    ACCEPT_POLICIES.save(deps.storage, info.sender.as_str(), &policy)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_accept_policy")
+        .add_attribute("addr", info.sender))
+}
+
+pub fn execute_set_forward(
+    deps: DepsMut,
+    info: MessageInfo,
+    to: String,
+) -> Result<Response, ContractError> {
+    let to = deps.api.addr_validate(&to)?;
+    if to == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    PENDING_FORWARDS.save(deps.storage, info.sender.as_str(), &to)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_forward")
+        .add_attribute("from", info.sender)
+        .add_attribute("to", to))
+}
+
+pub fn execute_confirm_forward(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: String,
+) -> Result<Response, ContractError> {
+    let from = deps.api.addr_validate(&from)?;
+    if PENDING_FORWARDS.may_load(deps.storage, from.as_str())? != Some(info.sender.clone()) {
+        return Err(ContractError::Unauthorized {});
+    }
+    // from's current forward is about to be replaced, so the new edge closes a loop if the chain
+    // starting at the sender passes through from anywhere, not only at its end.
+    let mut next = Some(info.sender.clone());
+    while let Some(addr) = next {
+        if addr == from {
+            return Err(ContractError::ForwardLoop {});
+        }
+        next = FORWARDS.may_load(deps.storage, addr.as_str())?;
+    }
+    PENDING_FORWARDS.remove(deps.storage, from.as_str());
+    FORWARDS.save(deps.storage, from.as_str(), &info.sender)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "confirm_forward")
+        .add_attribute("from", from)
+        .add_attribute("to", info.sender))
+}
+
+pub fn execute_remove_forward(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    PENDING_FORWARDS.remove(deps.storage, info.sender.as_str());
+    FORWARDS.remove(deps.storage, info.sender.as_str());
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_forward")
+        .add_attribute("from", info.sender))
+}
+
+pub fn execute_move_balance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    if !FORWARDS.has(deps.storage, info.sender.as_str()) {
+        return Err(ContractError::NoForward {});
+    }
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    let to = resolve_forward(deps.storage, &info.sender)?;
+    let amount = POTS
+        .may_load(deps.storage, (info.sender.as_str(), &asset))?
+        .map(|pot| pot.collected)
+        .unwrap_or_default();
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let event = transfer_between_pots(deps.storage, &env, &info.sender, &to, &asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "move_balance")
+        .add_event(event))
+}
+
+pub fn execute_transfer_balance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: String,
+    amount: Uint128,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    let to = deps.api.addr_validate(&to)?;
+    let to = resolve_forward(deps.storage, &to)?;
+    if to == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    check_accepts(deps.storage, &to, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    let event = transfer_between_pots(deps.storage, &env, &info.sender, &to, &asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_balance")
+        .add_event(event))
+}
+
+/// check_accepts fails with Rejected unless recipient's accept policy takes credits from sender.
+fn check_accepts(storage: &dyn Storage, recipient: &Addr, sender: &Addr) -> Result<(), ContractError> {
+    let policy = ACCEPT_POLICIES.may_load(storage, recipient.as_str())?.unwrap_or_default();
+    if !policy.accepts(sender) {
+        return Err(ContractError::Rejected { recipient: recipient.to_string() });
+    }
+    Ok(())
+}
+
+/// execute_send credits contract's pot directly, without following forwards, so the balance is
+/// where the hook expects it. The hook runs as a plain message: if it fails, the transfer reverts.
+pub fn execute_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    asset: Option<String>,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    if contract == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+    check_accepts(deps.storage, &contract, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    let event = transfer_between_pots(deps.storage, &env, &info.sender, &contract, &asset, amount)?;
+    let hook = PotReceiveMsg {
+        sender: info.sender.into_string(),
+        asset,
+        amount,
+        msg,
+    }
+    .into_cosmos_msg(&contract)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "send")
+        .add_event(event)
+        .add_message(hook))
+}
+
+/// transfer_between_pots moves amount from one pot to another, recording both sides in the ledger.
+/// The tokens stay in the contract, so liabilities and withdrawal statistics do not change.
+fn transfer_between_pots(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    asset: &str,
+    amount: Uint128,
+) -> Result<Event, ContractError> {
+    let balance = POTS
+        .may_load(storage, (from.as_str(), asset))?
+        .map(|pot| pot.collected)
+        .unwrap_or_default();
+    if amount > balance {
+        return Err(ContractError::InsufficientBalance { balance: balance.to_string() });
+    }
+    let pot = debit_pot(storage, from, asset, amount)?;
+    ACCOUNTS.update(storage, (from.as_str(), asset), |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.transferred += amount;
+        Ok(totals)
+    })?;
+    save_ledger_entry(storage, from, &LedgerEntry {
+        kind: LedgerKind::TransferOut,
+        counterparty: to.clone(),
+        amount,
+        balance: pot.collected,
+        asset: asset.to_string(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+    let to_pot = credit_beneficiary(storage, env, asset, LedgerKind::TransferIn, from, to, amount)?;
+
+    Ok(PotTransferEvent {
+        from: from.to_string(),
+        to: to.to_string(),
+        asset: asset.to_string(),
+        amount,
+        from_balance: pot.collected,
+        to_balance: to_pot.collected,
+    }.into())
+}
+
+pub fn execute_save_split_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    recipients: Vec<String>,
+    weights: Vec<u64>,
+    immutable: bool,
+) -> Result<Response, ContractError> {
+    if recipients.is_empty()
+        || recipients.len() != weights.len()
+        || weights.contains(&0)
+        || total_weight(&weights).is_none()
+    {
+        return Err(ContractError::InvalidTemplate {});
+    }
+    let recipients = recipients
+        .iter()
+        .map(|addr| deps.api.addr_validate(addr))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let id = match TEMPLATE_NAMES.may_load(deps.storage, (info.sender.as_str(), &name))? {
+        Some(id) => {
+            if TEMPLATES.load(deps.storage, id)?.immutable {
+                return Err(ContractError::TemplateImmutable { name });
+            }
+            id
+        }
+        None => {
+            let id = TEMPLATE_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+            TEMPLATE_SEQ.save(deps.storage, &id)?;
+            TEMPLATE_NAMES.save(deps.storage, (info.sender.as_str(), &name), &id)?;
+            id
+        }
+    };
+    TEMPLATES.save(deps.storage, id, &SplitTemplate {
+        owner: info.sender,
+        name: name.clone(),
+        recipients,
+        weights,
+        immutable,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "save_split_template")
+        .add_attribute("id", id.to_string())
+        .add_attribute("name", name))
+}
+
+/// total_weight adds up split weights, or returns None if they overflow.
+fn total_weight(weights: &[u64]) -> Option<u64> {
+    weights.iter().try_fold(0u64, |total, weight| total.checked_add(*weight))
+}
+
+pub fn execute_delete_split_template(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    let id = TEMPLATE_NAMES.load(deps.storage, (info.sender.as_str(), &name))?;
+    if TEMPLATES.load(deps.storage, id)?.immutable {
+        return Err(ContractError::TemplateImmutable { name });
+    }
+    TEMPLATE_NAMES.remove(deps.storage, (info.sender.as_str(), &name));
+    TEMPLATES.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_attribute("action", "delete_split_template")
+        .add_attribute("id", id.to_string())
+        .add_attribute("name", name))
+}
+
+/// received_funds returns the asset, depositor and amount of a deposit: either the cw20 amount
+/// wrapped by an accepted token contract or the single accepted native coin sent along.
+fn received_funds(
+    deps: Deps,
+    info: MessageInfo,
+    wrapped: Option<Cw20ReceiveMsg>,
+) -> Result<(String, Addr, Uint128), ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (asset, depositor, amount) = match wrapped {
+        // An accepted cw20 contract deposits on behalf of the wrapped sender.
+        Some(wrapped) => {
+            if !config.cw20_tokens.contains(&info.sender) {
+                return Err(ContractError::UnacceptedAsset { asset: info.sender.into_string() });
+            }
+            (info.sender.into_string(), deps.api.addr_validate(&wrapped.sender)?, wrapped.amount)
+        }
+        // Otherwise the sender deposits the native coin sent along.
+        None => match info.funds.as_slice() {
+            [] => return Err(ContractError::CustomError { val:"No token sent".to_string() }),
+            [coin] => {
+                if !config.native_denoms.contains(&coin.denom) {
+                    return Err(ContractError::UnacceptedAsset { asset: coin.denom.clone() });
+                }
+                (coin.denom.clone(), info.sender.clone(), coin.amount)
+            }
+            _ => return Err(ContractError::CustomError { val:"Send a single coin".to_string() }),
+        },
+    };
+    if amount == Uint128::new(0) {
+        return Err(ContractError::CustomError { val:"No token sent".to_string() });
+    }
+    Ok((asset, depositor, amount))
+}
+
+/// credit_deposit logs a deposit of amount to target, or to the account target forwards to, and
+/// credits it, returning its pot_deposit event. Only deposits count target as a beneficiary.
+fn credit_deposit(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    depositor: &Addr,
+    target: &Addr,
+    amount: Uint128,
+) -> StdResult<Event> {
+    let target = &resolve_forward(storage, target)?;
+    let deposit_id = save_deposit(storage, &Deposit {
+        depositor: depositor.clone(),
+        recipient: target.clone(),
+        amount,
+        asset: asset.to_string(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+    if !BENEFICIARIES.has(storage, (asset, target.as_str())) {
+        BENEFICIARIES.save(storage, (asset, target.as_str()), &Empty {})?;
+        STATS.update(storage, asset, |stats| -> StdResult<_> {
+            let mut stats = stats.unwrap_or_default();
+            stats.beneficiaries += 1;
+            Ok(stats)
+        })?;
+    }
+    let pot = credit_beneficiary(storage, env, asset, LedgerKind::Deposit, depositor, target, amount)?;
+    Ok(PotDepositEvent {
+        deposit_id,
+        depositor: depositor.to_string(),
+        recipient: target.to_string(),
+        asset: asset.to_string(),
+        amount,
+        balance: pot.collected,
+    }.into())
+}
+
+/// mint_claim logs a deposit of amount to target, or to the account target forwards to, and
+/// returns the message minting it in claim tokens along with its claim_minted event. Pots are not
+/// credited: the asset stays owed to whoever holds the tokens.
+fn mint_claim(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    depositor: &Addr,
+    target: &Addr,
+    token: &Addr,
+    amount: Uint128,
+) -> StdResult<(CosmosMsg, Event)> {
+    let target = resolve_forward(storage, target)?;
+    let deposit_id = save_deposit(storage, &Deposit {
+        depositor: depositor.clone(),
+        recipient: target.clone(),
+        amount,
+        asset: asset.to_string(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+    let msg = Cw20Contract(token.clone()).call(Cw20ExecuteMsg::Mint {
+        recipient: target.to_string(),
+        amount,
+    })?;
+    let event = ClaimMintedEvent {
+        deposit_id,
+        depositor: depositor.to_string(),
+        recipient: target.into_string(),
+        asset: asset.to_string(),
+        token: token.to_string(),
+        amount,
+    };
+    Ok((msg, event.into()))
+}
+
+fn record_deposit_stats(storage: &mut dyn Storage, asset: &str, amount: Uint128) -> StdResult<()> {
+    STATS.update(storage, asset, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.deposited += amount;
+        stats.deposit_count += 1;
+        Ok(stats)
+    })?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_escrow_pot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    targets: [String; 2],
+    arbiter: String,
+    timeout: Option<Expiration>,
+    milestones: Vec<MilestoneMsg>,
+    wrapped: Option<Cw20ReceiveMsg>,
+) -> Result<Response, ContractError> {
+    let (asset, depositor, amount) = received_funds(deps.as_ref(), info, wrapped)?;
+    let arbiter = deps.api.addr_validate(&arbiter)?;
+    let recipients = targets
+        .iter()
+        .map(|target| deps.api.addr_validate(target))
+        .collect::<StdResult<Vec<_>>>()?;
+    if timeout.is_some_and(|timeout| timeout.is_expired(&env.block)) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    if !milestones.is_empty()
+        && (milestones.iter().any(|m| m.share.is_zero())
+            || milestones.iter().map(|m| m.share).sum::<Decimal>() != Decimal::one())
+    {
+        return Err(ContractError::InvalidMilestones {});
+    }
+
+    let escrow = Escrow {
+        depositor,
+        arbiter,
+        recipients,
+        asset,
+        share: amount.div(Uint128::new(2)),
+        timeout,
+        status: EscrowStatus::Locked,
+        milestones: milestones
+            .into_iter()
+            .map(|m| Milestone {
+                description: m.description,
+                share: m.share,
+                completed: false,
+            })
+            .collect(),
+        released: Uint128::zero(),
+    };
+    let id = ESCROW_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    ESCROW_SEQ.save(deps.storage, &id)?;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+    for recipient in &escrow.recipients {
+        LOCKED.update(deps.storage, (recipient.as_str(), &escrow.asset), |locked| -> StdResult<_> {
+            Ok(locked.unwrap_or_default().checked_add(escrow.share)?)
+        })?;
+    }
+    add_liability(deps.storage, &escrow.asset, escrow.locked())?;
+    record_deposit_stats(deps.storage, &escrow.asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_escrow_pot")
+        .add_attribute("id", id.to_string())
+        .add_attribute("asset", escrow.asset.clone())
+        .add_attribute("arbiter", escrow.arbiter.clone())
+        .add_event(EscrowCreatedEvent {
+            escrow_id: id,
+            depositor: escrow.depositor.into_string(),
+            arbiter: escrow.arbiter.into_string(),
+            recipient_1: escrow.recipients[0].to_string(),
+            recipient_2: escrow.recipients[1].to_string(),
+            asset: escrow.asset,
+            amount,
+            locked: escrow.share,
+        }.into()))
+}
+
+pub fn execute_release(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut escrow = load_open_escrow(deps.storage, id)?;
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = escrow.share - escrow.released;
+    for milestone in escrow.milestones.iter_mut() {
+        milestone.completed = true;
+    }
+    let events = release_tranche(deps.storage, &env, &mut escrow, amount)?;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "release")
+        .add_attribute("id", id.to_string())
+        .add_events(events))
+}
+
+pub fn execute_complete_milestone(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    milestone: usize,
+) -> Result<Response, ContractError> {
+    let mut escrow = load_open_escrow(deps.storage, id)?;
+    if info.sender != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+    let next = escrow
+        .milestones
+        .iter()
+        .position(|m| !m.completed)
+        .ok_or(ContractError::EscrowClosed { id })?;
+    if milestone != next {
+        return Err(ContractError::MilestoneOutOfOrder { expected: next });
+    }
+
+    let amount = escrow.tranche(milestone);
+    escrow.milestones[milestone].completed = true;
+    let events = release_tranche(deps.storage, &env, &mut escrow, amount)?;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "complete_milestone")
+        .add_attribute("id", id.to_string())
+        .add_attribute("milestone", milestone.to_string())
+        .add_events(events))
+}
+
+/// release_tranche unlocks amount for each recipient of escrow and credits it to their pots,
+/// closing the escrow once everything is released.
+fn release_tranche(
+    storage: &mut dyn Storage,
+    env: &Env,
+    escrow: &mut Escrow,
+    amount: Uint128,
+) -> StdResult<Vec<Event>> {
+    let mut events = Vec::with_capacity(escrow.recipients.len());
+    for recipient in &escrow.recipients {
+        unlock(storage, recipient, &escrow.asset, amount)?;
+        events.push(credit_deposit(storage, env, &escrow.asset, &escrow.depositor, recipient, amount)?);
+    }
+    escrow.released += amount;
+    if escrow.released == escrow.share {
+        escrow.status = EscrowStatus::Released;
+    }
+    Ok(events)
+}
+
+pub fn execute_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut escrow = load_open_escrow(deps.storage, id)?;
+    let timed_out = escrow.timeout.is_some_and(|timeout| timeout.is_expired(&env.block));
+    if info.sender != escrow.arbiter && !(info.sender == escrow.depositor && timed_out) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for recipient in &escrow.recipients {
+        unlock(deps.storage, recipient, &escrow.asset, escrow.share - escrow.released)?;
+    }
+    // The funds stay owed, to the depositor now, so liabilities do not move.
+    let amount = escrow.locked();
+    let pot = credit_beneficiary(
+        deps.storage,
+        &env,
+        &escrow.asset,
+        LedgerKind::Refund,
+        &info.sender,
+        &escrow.depositor,
+        amount,
+    )?;
+    escrow.status = EscrowStatus::Refunded;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "refund")
+        .add_attribute("id", id.to_string())
+        .add_event(PotRefundEvent {
+            escrow_id: id,
+            depositor: escrow.depositor.into_string(),
+            asset: escrow.asset,
+            amount,
+            balance: pot.collected,
+        }.into()))
+}
+
+pub fn execute_create_joint_pot(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target_addr_1: String,
+    target_addr_2: String,
+    wrapped: Option<Cw20ReceiveMsg>,
+) -> Result<Response, ContractError> {
+    let (asset, depositor, amount) = received_funds(deps.as_ref(), info, wrapped)?;
+    let target_1 = deps.api.addr_validate(&target_addr_1)?;
+    let target_2 = deps.api.addr_validate(&target_addr_2)?;
+    if target_1 == target_2 {
+        return Err(ContractError::CustomError { val:"A joint pot needs two different targets".to_string() });
+    }
+
+    let (member_1, member_2) = joint_members(&target_1, &target_2);
+    let balance = JOINT_POTS.update(
+        deps.storage,
+        (member_1.as_str(), member_2.as_str(), &asset),
+        |balance| -> StdResult<_> { Ok(balance.unwrap_or_default().checked_add(amount)?) },
+    )?;
+    add_liability(deps.storage, &asset, amount)?;
+    record_deposit_stats(deps.storage, &asset, amount)?;
+    for member in [member_1, member_2] {
+        save_ledger_entry(deps.storage, member, &LedgerEntry {
+            kind: LedgerKind::Deposit,
+            counterparty: depositor.clone(),
+            amount,
+            balance,
+            asset: asset.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        })?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "create_joint_pot")
+        .add_attribute("asset", asset.clone())
+        .add_event(JointPotDepositEvent {
+            depositor: depositor.into_string(),
+            member_1: member_1.to_string(),
+            member_2: member_2.to_string(),
+            asset,
+            amount,
+            balance,
+        }.into()))
+}
+
+pub fn execute_propose_joint_withdrawal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    partner: String,
+    recipient: String,
+    amount: Uint128,
+    asset: Option<String>,
+) -> Result<Response, ContractError> {
+    let partner = deps.api.addr_validate(&partner)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    let (member_1, member_2) = joint_members(&info.sender, &partner);
+    let balance = JOINT_POTS
+        .may_load(deps.storage, (member_1.as_str(), member_2.as_str(), &asset))?
+        .unwrap_or_default();
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    if amount > balance {
+        return Err(ContractError::InsufficientBalance { balance: balance.to_string() });
+    }
+
+    let ttl = PROPOSAL_TTL.may_load(deps.storage)?.unwrap_or(DEFAULT_PROPOSAL_TTL);
+    let id = PROPOSAL_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    PROPOSAL_SEQ.save(deps.storage, &id)?;
+    JOINT_PROPOSALS.save(deps.storage, id, &JointProposal {
+        proposer: info.sender.clone(),
+        partner,
+        asset,
+        amount,
+        recipient,
+        expires: env.block.time.plus_seconds(ttl),
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_joint_withdrawal")
+        .add_attribute("id", id.to_string())
+        .add_attribute("proposer", info.sender))
+}
+
+pub fn execute_confirm_joint_withdrawal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let proposal = JOINT_PROPOSALS.load(deps.storage, id)?;
+    if info.sender != proposal.partner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time >= proposal.expires {
+        return Err(ContractError::ProposalExpired { id });
+    }
+
+    let (member_1, member_2) = joint_members(&proposal.proposer, &proposal.partner);
+    let key = (member_1.as_str(), member_2.as_str(), proposal.asset.as_str());
+    let available = JOINT_POTS.may_load(deps.storage, key)?.unwrap_or_default();
+    let balance = available
+        .checked_sub(proposal.amount)
+        .map_err(|_| ContractError::InsufficientBalance { balance: available.to_string() })?;
+    if balance.is_zero() {
+        JOINT_POTS.remove(deps.storage, key);
+    } else {
+        JOINT_POTS.save(deps.storage, key, &balance)?;
+    }
+    JOINT_PROPOSALS.remove(deps.storage, id);
+    sub_liability(deps.storage, &proposal.asset, proposal.amount)?;
+    STATS.update(deps.storage, &proposal.asset, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.withdrawn += proposal.amount;
+        Ok(stats)
+    })?;
+    for member in [member_1, member_2] {
+        save_ledger_entry(deps.storage, member, &LedgerEntry {
+            kind: LedgerKind::Withdrawal,
+            counterparty: proposal.recipient.clone(),
+            amount: proposal.amount,
+            balance,
+            asset: proposal.asset.clone(),
+            height: env.block.height,
+            time: env.block.time,
+        })?;
+    }
+    let msg = transfer_msg(&ASSETS.load(deps.storage, &proposal.asset)?, &proposal.recipient, proposal.amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "confirm_joint_withdrawal")
+        .add_attribute("id", id.to_string())
+        .add_message(msg)
+        .add_event(JointPotWithdrawEvent {
+            proposal_id: id,
+            member_1: member_1.to_string(),
+            member_2: member_2.to_string(),
+            recipient: proposal.recipient.to_string(),
+            asset: proposal.asset,
+            amount: proposal.amount,
+            balance,
+        }.into()))
+}
+
+pub fn execute_set_proposal_ttl(
+    deps: DepsMut,
+    info: MessageInfo,
+    seconds: u64,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Operator])?;
+    PROPOSAL_TTL.save(deps.storage, &seconds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_proposal_ttl")
+        .add_attribute("seconds", seconds.to_string()))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_create_claim_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    asset: Option<String>,
+    code_id: u64,
+    name: String,
+    symbol: String,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Operator])?;
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    if CLAIM_TOKENS.has(deps.storage, &asset) {
+        return Err(ContractError::ClaimTokenExists { asset });
+    }
+    PENDING_CLAIM_TOKEN.save(deps.storage, &asset)?;
+    let msg = WasmMsg::Instantiate {
+        admin: None,
+        code_id,
+        msg: to_binary(&ClaimTokenInstantiateMsg {
+            name,
+            symbol,
+            decimals,
+            initial_balances: vec![],
+            mint: Some(MinterResponse {
+                minter: env.contract.address.into_string(),
+                cap: None,
+            }),
+        })?,
+        funds: vec![],
+        label: format!("{} claims", asset),
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "create_claim_token")
+        .add_attribute("asset", asset)
+        .add_submessage(SubMsg::reply_on_success(msg, CLAIM_TOKEN_REPLY_ID)))
+}
+
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    pause: &PauseState,
+    wrapped: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_binary(&wrapped.msg)? {
+        ReceiveMsg::CreatePot {
+            target_addr_1,
+            target_addr_2,
+            template,
+            on_reject,
+            mint_claims,
+        } => {
+            check_deposits_not_paused(pause)?;
+            execute_create_pot(
+                deps,
+                env,
+                info,
+                target_addr_1,
+                target_addr_2,
+                template,
+                on_reject,
+                mint_claims,
+                Some(wrapped),
+            )
+        }
+        ReceiveMsg::CreateEscrowPot {
+            target_addr_1,
+            target_addr_2,
+            arbiter,
+            timeout,
+            milestones,
+        } => {
+            check_deposits_not_paused(pause)?;
+            execute_create_escrow_pot(
+                deps,
+                env,
+                info,
+                [target_addr_1, target_addr_2],
+                arbiter,
+                timeout,
+                milestones,
+                Some(wrapped),
+            )
+        }
+        ReceiveMsg::CreateJointPot { target_addr_1, target_addr_2 } => {
+            check_deposits_not_paused(pause)?;
+            execute_create_joint_pot(deps, env, info, target_addr_1, target_addr_2, Some(wrapped))
+        }
+        ReceiveMsg::Contribute { id } => {
+            check_deposits_not_paused(pause)?;
+            execute_contribute(deps, env, info, id, Some(wrapped))
+        }
+        ReceiveMsg::Redeem {} => {
+            check_deposits_not_paused(pause)?;
+            execute_redeem(deps, env, info, wrapped)
+        }
+        ReceiveMsg::Send { .. } => {
+            Err(ContractError::CustomError { val:"Send with create_pot to deposit".to_string() })
+        }
+    }
+}
+
+/// execute_redeem burns the claim tokens received and credits the same amount of their asset to
+/// the holder's pot. The asset was already counted as a liability when the tokens were minted.
+fn execute_redeem(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapped: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let asset = CLAIM_TOKEN_ASSETS
+        .may_load(deps.storage, info.sender.as_str())?
+        .ok_or_else(|| ContractError::UnacceptedAsset { asset: info.sender.to_string() })?;
+    let holder = deps.api.addr_validate(&wrapped.sender)?;
+    if wrapped.amount.is_zero() {
+        return Err(ContractError::CustomError { val:"No token sent".to_string() });
+    }
+    let pot = credit_beneficiary(
+        deps.storage,
+        &env,
+        &asset,
+        LedgerKind::Redemption,
+        &info.sender,
+        &holder,
+        wrapped.amount,
+    )?;
+    let burn = Cw20Contract(info.sender.clone()).call(Cw20ExecuteMsg::Burn { amount: wrapped.amount })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "redeem")
+        .add_event(ClaimRedeemedEvent {
+            holder: holder.into_string(),
+            asset,
+            token: info.sender.into_string(),
+            amount: wrapped.amount,
+            balance: pot.collected,
+        }.into())
+        .add_message(burn))
+}
+
+pub fn execute_set_token_view(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: Option<String>,
+    name: String,
+    symbol: String,
+    decimals: u8,
+) -> Result<Response, ContractError> {
+    ensure_any_role(deps.storage, &info.sender, &[Role::Owner, Role::Operator])?;
+    let asset = asset_key(&resolve_asset(deps.as_ref(), asset)?);
+    TOKEN_VIEW.save(deps.storage, &TokenView { asset: asset.clone(), name, symbol, decimals })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_token_view")
+        .add_attribute("asset", asset))
+}
+
+fn load_open_escrow(storage: &dyn Storage, id: u64) -> Result<Escrow, ContractError> {
+    let escrow = ESCROWS.load(storage, id)?;
+    if escrow.status != EscrowStatus::Locked {
+        return Err(ContractError::EscrowClosed { id });
+    }
+    Ok(escrow)
+}
+
+fn unlock(storage: &mut dyn Storage, addr: &Addr, asset: &str, amount: Uint128) -> StdResult<()> {
+    let locked = LOCKED.load(storage, (addr.as_str(), asset))?.checked_sub(amount)?;
+    if locked.is_zero() {
+        LOCKED.remove(storage, (addr.as_str(), asset));
+    } else {
+        LOCKED.save(storage, (addr.as_str(), asset), &locked)?;
+    }
+    Ok(())
+}
+
+pub fn execute_create_campaign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    targets: [String; 2],
+    asset: String,
+    goal: Uint128,
+    deadline: Expiration,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !config.native_denoms.contains(&asset) && !config.cw20_tokens.iter().any(|addr| addr == &asset) {
+        return Err(ContractError::UnacceptedAsset { asset });
+    }
+    if goal.is_zero() {
+        return Err(ContractError::CustomError { val:"Goal must be positive".to_string() });
+    }
+    if matches!(deadline, Expiration::Never {}) || deadline.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    let recipients = targets
+        .iter()
+        .map(|target| deps.api.addr_validate(target))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let id = CAMPAIGN_SEQ.may_load(deps.storage)?.unwrap_or_default() + 1;
+    CAMPAIGN_SEQ.save(deps.storage, &id)?;
+    CAMPAIGNS.save(deps.storage, id, &Campaign {
+        creator: info.sender,
+        recipients,
+        asset,
+        goal,
+        deadline,
+        raised: Uint128::zero(),
+        refunded: Uint128::zero(),
+        contributors: 0,
+        status: CampaignStatus::Open,
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_campaign")
+        .add_attribute("id", id.to_string()))
+}
+
+pub fn execute_contribute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    wrapped: Option<Cw20ReceiveMsg>,
+) -> Result<Response, ContractError> {
+    let (asset, contributor, amount) = received_funds(deps.as_ref(), info, wrapped)?;
+    let mut campaign = CAMPAIGNS.load(deps.storage, id)?;
+    if campaign.status != CampaignStatus::Open || campaign.deadline.is_expired(&env.block) {
+        return Err(ContractError::CampaignClosed { id });
+    }
+    if asset != campaign.asset {
+        return Err(ContractError::UnacceptedAsset { asset });
+    }
+
+    let contributed = CONTRIBUTIONS.may_load(deps.storage, (id, contributor.as_str()))?;
+    if contributed.is_none() {
+        campaign.contributors += 1;
+    }
+    let contribution = contributed.unwrap_or_default().checked_add(amount).map_err(StdError::from)?;
+    CONTRIBUTIONS.save(deps.storage, (id, contributor.as_str()), &contribution)?;
+    campaign.raised = campaign.raised.checked_add(amount).map_err(StdError::from)?;
+    CAMPAIGNS.save(deps.storage, id, &campaign)?;
+    add_liability(deps.storage, &asset, amount)?;
+    record_deposit_stats(deps.storage, &asset, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "contribute")
+        .add_attribute("id", id.to_string())
+        .add_attribute("contributor", contributor.clone())
+        .add_attribute("amount", amount)
+        .add_attribute("raised", campaign.raised)
+        .add_event(CampaignContributionEvent {
+            campaign_id: id,
+            contributor: contributor.into_string(),
+            asset,
+            amount,
+            contribution,
+            raised: campaign.raised,
+        }.into()))
+}
+
+/// execute_finalize splits the funds of a campaign that reached its goal between its recipients,
+/// credited on behalf of the campaign creator.
+pub fn execute_finalize(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let mut campaign = CAMPAIGNS.load(deps.storage, id)?;
+    if campaign.status != CampaignStatus::Open {
+        return Err(ContractError::CampaignClosed { id });
+    }
+    if campaign.raised < campaign.goal {
+        return Err(ContractError::GoalNotReached { id });
+    }
+
+    let share = campaign.raised.div(Uint128::from(campaign.recipients.len() as u128));
+    let mut events = Vec::with_capacity(campaign.recipients.len());
+    for recipient in &campaign.recipients {
+        events.push(credit_deposit(deps.storage, &env, &campaign.asset, &campaign.creator, recipient, share)?);
+    }
+    // Like CreatePot, the rounding remainder is not owed to anyone.
+    let remainder = campaign.raised - share * Uint128::from(campaign.recipients.len() as u128);
+    sub_liability(deps.storage, &campaign.asset, remainder)?;
+    campaign.status = CampaignStatus::Funded;
+    CAMPAIGNS.save(deps.storage, id, &campaign)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "finalize")
+        .add_attribute("id", id.to_string())
+        .add_events(events))
+}
+
+pub fn execute_claim_refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut campaign = CAMPAIGNS.load(deps.storage, id)?;
+    if !campaign.failed(&env.block) {
+        return Err(ContractError::CampaignNotFailed { id });
+    }
+    let amount = CONTRIBUTIONS
+        .may_load(deps.storage, (id, info.sender.as_str()))?
+        .ok_or(ContractError::NoContribution {})?;
+
+    CONTRIBUTIONS.remove(deps.storage, (id, info.sender.as_str()));
+    campaign.refunded += amount;
+    CAMPAIGNS.save(deps.storage, id, &campaign)?;
+    let campaign_addr = env.contract.address.clone();
+    let pot = credit_beneficiary(
+        deps.storage,
+        &env,
+        &campaign.asset,
+        LedgerKind::Refund,
+        &campaign_addr,
+        &info.sender,
+        amount,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_refund")
+        .add_attribute("id", id.to_string())
+        .add_event(CampaignRefundEvent {
+            campaign_id: id,
+            contributor: info.sender.into_string(),
+            asset: campaign.asset,
+            amount,
+            balance: pot.collected,
+        }.into()))
+}
+
+/// credit_beneficiary adds amount to target's pot and records it in the ledger and account totals.
+fn credit_beneficiary(
+    storage: &mut dyn Storage,
+    env: &Env,
+    asset: &str,
+    kind: LedgerKind,
+    counterparty: &Addr,
+    target: &Addr,
+    amount: Uint128,
+) -> StdResult<Pot> {
+    let pot = credit_pot(storage, target, asset, amount)?;
+    ACCOUNTS.update(storage, (target.as_str(), asset), |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.received += amount;
+        Ok(totals)
+    })?;
+    save_ledger_entry(storage, target, &LedgerEntry {
+        kind,
+        counterparty: counterparty.clone(),
+        amount,
+        balance: pot.collected,
+        asset: asset.to_string(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+    Ok(pot)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        (WITHDRAW_REPLY_ID, SubMsgResult::Err(error)) => reply_withdraw_failed(deps, env, error),
+        (WITHDRAW_REPLY_ID, SubMsgResult::Ok(_)) => {
+            PENDING_WITHDRAWAL.remove(deps.storage);
+            Ok(Response::new())
+        }
+        (CLAIM_TOKEN_REPLY_ID, SubMsgResult::Ok(res)) => reply_claim_token_created(deps, res),
+        (id, _) => Err(ContractError::UnknownReplyId { id }),
+    }
+}
+
+/// reply_claim_token_created records the claim token address reported by the instantiation.
+fn reply_claim_token_created(deps: DepsMut, res: SubMsgResponse) -> Result<Response, ContractError> {
+    let token = res
+        .events
+        .iter()
+        .filter(|event| event.ty == "instantiate")
+        .flat_map(|event| &event.attributes)
+        .find(|attr| attr.key == "_contract_address")
+        .ok_or_else(|| StdError::generic_err("No contract address in instantiate reply"))?;
+    let token = deps.api.addr_validate(&token.value)?;
+    let asset = PENDING_CLAIM_TOKEN.load(deps.storage)?;
+    PENDING_CLAIM_TOKEN.remove(deps.storage);
+    CLAIM_TOKENS.save(deps.storage, &asset, &token)?;
+    CLAIM_TOKEN_ASSETS.save(deps.storage, token.as_str(), &asset)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_token_created")
+        .add_attribute("asset", asset)
+        .add_attribute("token", token))
+}
+
+/// reply_withdraw_failed undoes the pending withdrawal: the pot, liabilities, totals and allowance
+/// get the amount back and the statement records the failure.
+fn reply_withdraw_failed(deps: DepsMut, env: Env, error: String) -> Result<Response, ContractError> {
+    let pending = PENDING_WITHDRAWAL.load(deps.storage)?;
+    PENDING_WITHDRAWAL.remove(deps.storage);
+    let PendingWithdrawal { owner, recipient, spender, asset, amount } = pending;
+
+    let pot = credit_pot(deps.storage, &owner, &asset, amount)?;
+    add_liability(deps.storage, &asset, amount)?;
+    ACCOUNTS.update(deps.storage, (owner.as_str(), &asset), |totals| -> StdResult<_> {
+        let mut totals = totals.unwrap_or_default();
+        totals.withdrawn = totals.withdrawn.checked_sub(amount)?;
+        Ok(totals)
+    })?;
+    STATS.update(deps.storage, &asset, |stats| -> StdResult<_> {
+        let mut stats = stats.unwrap_or_default();
+        stats.withdrawn = stats.withdrawn.checked_sub(amount)?;
+        Ok(stats)
+    })?;
+    if let Some(spender) = spender {
+        let key = (owner.as_str(), spender.as_str(), asset.as_str());
+        if let Some(mut allowance) = WITHDRAW_ALLOWANCES.may_load(deps.storage, key)? {
+            allowance.allowance += amount;
+            WITHDRAW_ALLOWANCES.save(deps.storage, key, &allowance)?;
+        }
+    }
+    save_ledger_entry(deps.storage, &owner, &LedgerEntry {
+        kind: LedgerKind::FailedWithdrawal,
+        counterparty: recipient.clone(),
+        amount,
+        balance: pot.collected,
+        asset: asset.clone(),
+        height: env.block.height,
+        time: env.block.time,
+    })?;
+
+    Ok(Response::new().add_event(PotWithdrawFailedEvent {
+        owner: owner.into_string(),
+        recipient: recipient.into_string(),
+        asset,
+        amount,
+        balance: pot.collected,
+        error,
+    }.into()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::QueryOwner {} => to_binary(&query_owner(deps)?),
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::ContractInfo {} => to_binary(&query_contract_info(deps)?),
+        QueryMsg::GetPot { addr, asset } => to_binary(&query_pot(deps, &addr, asset)?),
+        QueryMsg::GetPots { addrs, asset } => to_binary(&query_pots(deps, addrs, asset)?),
+        QueryMsg::Escrow { id } => to_binary(&query_escrow(deps, id)?),
+        QueryMsg::Milestones { id } => to_binary(&query_milestones(deps, id)?),
+        QueryMsg::AcceptPolicy { addr } => to_binary(&query_accept_policy(deps, addr)?),
+        QueryMsg::Forward { addr } => to_binary(&query_forward(deps, addr)?),
+        QueryMsg::ListSplitTemplates {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_split_templates(deps, owner, start_after, limit)?),
+        QueryMsg::Campaign { id } => to_binary(&query_campaign(deps, env, id)?),
+        QueryMsg::Contribution { id, contributor } => {
+            to_binary(&query_contribution(deps, id, contributor)?)
+        }
+        QueryMsg::JointPot {
+            addr_1,
+            addr_2,
+            asset,
+        } => to_binary(&query_joint_pot(deps, addr_1, addr_2, asset)?),
+        QueryMsg::JointProposal { id } => to_binary(&query_joint_proposal(deps, id)?),
+        QueryMsg::GetAccount { addr, asset } => to_binary(&query_account(deps, addr, asset)?),
+        QueryMsg::WithdrawAllowance {
+            owner,
+            spender,
+            asset,
+        } => to_binary(&query_withdraw_allowance(deps, owner, spender, asset)?),
+        QueryMsg::PauseState {} => to_binary(&query_pause_state(deps)?),
+        QueryMsg::Roles { addr } => to_binary(&query_roles(deps, addr)?),
+        QueryMsg::RoleMembers {
+            role,
+            start_after,
+            limit,
+        } => to_binary(&query_role_members(deps, role, start_after, limit)?),
+        QueryMsg::DepositsBySender {
+            sender,
+            start_after,
+            limit,
+        } => to_binary(&query_deposits_by_sender(deps, sender, start_after, limit)?),
+        QueryMsg::DepositsByRecipient {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_deposits_by_recipient(deps, recipient, start_after, limit)?),
+        QueryMsg::Statement {
+            addr,
+            start_after,
+            limit,
+        } => to_binary(&query_statement(deps, addr, start_after, limit)?),
+        QueryMsg::Solvency {} => to_binary(&query_solvency(deps, env)?),
+        QueryMsg::Stats {} => to_binary(&query_stats(deps)?),
+        QueryMsg::ClaimToken { asset } => to_binary(&query_claim_token(deps, asset)?),
+        QueryMsg::Balance { address } => to_binary(&query_balance(deps, address)?),
+        QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
+        QueryMsg::AllAccounts { start_after, limit } => {
+            to_binary(&query_all_accounts(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_owner(deps: Deps) -> StdResult<Config> {
+    CONFIG.load(deps.storage)
+}
+
+fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        owner: config.owner.into_string(),
+        native_denoms: config.native_denoms,
+        cw20_tokens: config.cw20_tokens.into_iter().map(Addr::into_string).collect(),
+        primary_asset: asset_key(&config.primary_asset),
+        pause: PAUSE.may_load(deps.storage)?.unwrap_or_default(),
+        default_limit: DEFAULT_LIMIT,
+        max_limit: MAX_LIMIT,
+        proposal_ttl: PROPOSAL_TTL.may_load(deps.storage)?.unwrap_or(DEFAULT_PROPOSAL_TTL),
+    })
+}
+
+fn query_contract_info(deps: Deps) -> StdResult<ContractInfoResponse> {
+    let version = get_contract_version(deps.storage)?;
+    let instantiation = INSTANTIATION.load(deps.storage)?;
+    Ok(ContractInfoResponse {
+        contract: version.contract,
+        version: version.version,
+        features: FEATURES.iter().map(|feature| feature.to_string()).collect(),
+        instantiated_height: instantiation.height,
+        instantiated_time: instantiation.time,
+    })
+}
+
+fn query_pot(deps: Deps, addr: &str, asset: Option<String>) -> StdResult<PotResponse> {
+    let addr = deps.api.addr_validate(addr)?;
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    pot_response(deps, addr, asset)
+}
+
+fn query_pots(deps: Deps, addrs: Vec<String>, asset: Option<String>) -> StdResult<PotsResponse> {
+    if addrs.len() > MAX_LIMIT as usize {
+        return Err(StdError::generic_err(format!("At most {} addresses per query", MAX_LIMIT)));
+    }
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    let pots = addrs
+        .into_iter()
+        .map(|addr| pot_response(deps, deps.api.addr_validate(&addr)?, asset.clone()))
+        .collect::<StdResult<_>>()?;
+    Ok(PotsResponse { pots })
+}
+
+/// pot_response reports a zero balance for addresses without a pot instead of failing.
+fn pot_response(deps: Deps, addr: Addr, asset: String) -> StdResult<PotResponse> {
+    let collected = POTS
+        .may_load(deps.storage, (addr.as_str(), &asset))?
+        .map(|pot| pot.collected)
+        .unwrap_or_default();
+    let has_history = ACCOUNTS.has(deps.storage, (addr.as_str(), &asset));
+    let locked = LOCKED.may_load(deps.storage, (addr.as_str(), &asset))?.unwrap_or_default();
+    Ok(PotResponse {
+        target_addr: addr.into_string(),
+        collected: collected.to_string(),
+        asset,
+        has_history,
+        locked: locked.to_string(),
+    })
+}
+
+fn query_account(deps: Deps, addr: String, asset: Option<String>) -> StdResult<AccountResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    let balance = POTS
+        .may_load(deps.storage, (addr.as_str(), &asset))?
+        .map(|pot| pot.collected)
+        .unwrap_or_default();
+    let locked = LOCKED.may_load(deps.storage, (addr.as_str(), &asset))?.unwrap_or_default();
+    let totals = ACCOUNTS.may_load(deps.storage, (addr.as_str(), &asset))?.unwrap_or_default();
+    Ok(AccountResponse {
+        addr: addr.into_string(),
+        asset,
+        balance,
+        locked,
+        received: totals.received,
+        withdrawn: totals.withdrawn,
+        transferred: totals.transferred,
+    })
+}
+
+fn query_escrow(deps: Deps, id: u64) -> StdResult<EscrowResponse> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    Ok(EscrowResponse {
+        id,
+        depositor: escrow.depositor.into_string(),
+        arbiter: escrow.arbiter.into_string(),
+        recipients: escrow.recipients.into_iter().map(Addr::into_string).collect(),
+        asset: escrow.asset,
+        share: escrow.share,
+        timeout: escrow.timeout,
+        status: escrow.status,
+        released: escrow.released,
+    })
+}
+
+fn query_milestones(deps: Deps, id: u64) -> StdResult<MilestonesResponse> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    let milestones = escrow
+        .milestones
+        .iter()
+        .enumerate()
+        .map(|(index, m)| MilestoneInfo {
+            index,
+            description: m.description.clone(),
+            share: m.share,
+            amount: escrow.tranche(index),
+            completed: m.completed,
+        })
+        .collect();
+    Ok(MilestonesResponse { milestones })
+}
+
+fn query_accept_policy(deps: Deps, addr: String) -> StdResult<AcceptPolicy> {
+    let addr = deps.api.addr_validate(&addr)?;
+    Ok(ACCEPT_POLICIES.may_load(deps.storage, addr.as_str())?.unwrap_or_default())
+}
+
+fn query_forward(deps: Deps, addr: String) -> StdResult<ForwardResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    Ok(ForwardResponse {
+        forward: FORWARDS.may_load(deps.storage, addr.as_str())?.map(Addr::into_string),
+        pending: PENDING_FORWARDS.may_load(deps.storage, addr.as_str())?.map(Addr::into_string),
+        resolved: resolve_forward(deps.storage, &addr)?.into_string(),
+    })
+}
+
+fn query_split_templates(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<SplitTemplatesResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let templates = TEMPLATE_NAMES
+        .prefix(owner.as_str())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, id) = item?;
+            let template = TEMPLATES.load(deps.storage, id)?;
+            Ok(SplitTemplateInfo {
+                id,
+                name: template.name,
+                recipients: template.recipients.into_iter().map(Addr::into_string).collect(),
+                weights: template.weights,
+                immutable: template.immutable,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(SplitTemplatesResponse { templates })
+}
+
+fn query_campaign(deps: Deps, env: Env, id: u64) -> StdResult<CampaignResponse> {
+    let campaign = CAMPAIGNS.load(deps.storage, id)?;
+    Ok(CampaignResponse {
+        id,
+        failed: campaign.failed(&env.block),
+        creator: campaign.creator.into_string(),
+        recipients: campaign.recipients.into_iter().map(Addr::into_string).collect(),
+        asset: campaign.asset,
+        goal: campaign.goal,
+        deadline: campaign.deadline,
+        raised: campaign.raised,
+        refunded: campaign.refunded,
+        contributors: campaign.contributors,
+        // goal is never zero, so this only fails once progress exceeds Decimal::MAX
+        progress: Decimal::checked_from_ratio(campaign.raised, campaign.goal).unwrap_or(Decimal::MAX),
+        status: campaign.status,
+    })
+}
+
+fn query_contribution(deps: Deps, id: u64, contributor: String) -> StdResult<ContributionResponse> {
+    let contributor = deps.api.addr_validate(&contributor)?;
+    let amount = CONTRIBUTIONS
+        .may_load(deps.storage, (id, contributor.as_str()))?
+        .unwrap_or_default();
+    Ok(ContributionResponse { amount })
+}
+
+fn query_joint_pot(
+    deps: Deps,
+    addr_1: String,
+    addr_2: String,
+    asset: Option<String>,
+) -> StdResult<JointPotResponse> {
+    let addr_1 = deps.api.addr_validate(&addr_1)?;
+    let addr_2 = deps.api.addr_validate(&addr_2)?;
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    let (member_1, member_2) = joint_members(&addr_1, &addr_2);
+    let balance = JOINT_POTS
+        .may_load(deps.storage, (member_1.as_str(), member_2.as_str(), &asset))?
+        .unwrap_or_default();
+    Ok(JointPotResponse {
+        members: vec![member_1.to_string(), member_2.to_string()],
+        asset,
+        balance,
+    })
+}
+
+fn query_joint_proposal(deps: Deps, id: u64) -> StdResult<JointProposalResponse> {
+    let proposal = JOINT_PROPOSALS.load(deps.storage, id)?;
+    Ok(JointProposalResponse {
+        id,
+        proposer: proposal.proposer.into_string(),
+        partner: proposal.partner.into_string(),
+        asset: proposal.asset,
+        amount: proposal.amount,
+        recipient: proposal.recipient.into_string(),
+        expires: proposal.expires,
+    })
+}
+
+fn query_pause_state(deps: Deps) -> StdResult<PauseState> {
+    Ok(PAUSE.may_load(deps.storage)?.unwrap_or_default())
+}
+
+fn query_roles(deps: Deps, addr: String) -> StdResult<RolesResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let roles = Role::ALL
+        .into_iter()
+        .filter(|role| has_role(deps.storage, &addr, *role))
+        .collect();
+    Ok(RolesResponse { roles })
+}
+
+fn query_role_members(
+    deps: Deps,
+    role: Role,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RoleMembersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let members = ROLES
+        .prefix(role.as_str())
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(RoleMembersResponse { members })
+}
+
+fn query_deposits_by_sender(
+    deps: Deps,
+    sender: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DepositsResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let deposits = deposits()
+        .idx
+        .depositor
+        .prefix(sender.into_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, deposit)| deposit_info(id, deposit)))
+        .collect::<StdResult<_>>()?;
+    Ok(DepositsResponse { deposits })
+}
+
+fn query_deposits_by_recipient(
+    deps: Deps,
+    recipient: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<DepositsResponse> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let deposits = deposits()
+        .idx
+        .recipient
+        .prefix(recipient.into_string())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, deposit)| deposit_info(id, deposit)))
+        .collect::<StdResult<_>>()?;
+    Ok(DepositsResponse { deposits })
+}
+
+fn query_statement(
+    deps: Deps,
+    addr: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<StatementResponse> {
+    let addr = deps.api.addr_validate(&addr)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let entries = LEDGER
+        .prefix(addr.as_str())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(id, entry)| StatementEntry {
+                id,
+                kind: entry.kind,
+                counterparty: entry.counterparty.into_string(),
+                amount: entry.amount,
+                balance: entry.balance,
+                asset: entry.asset,
+                height: entry.height,
+                time: entry.time,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(StatementResponse { entries })
+}
+
+fn query_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    let assets = LIABILITIES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (asset, liabilities) = item?;
+            let balance = query_asset_balance(deps, &env, &ASSETS.load(deps.storage, &asset)?)?;
+            Ok(AssetSolvency {
+                asset,
+                liabilities,
+                balance,
+                solvent: balance >= liabilities,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    let solvent = assets.iter().all(|asset| asset.solvent);
+    Ok(SolvencyResponse { assets, solvent })
+}
+
+fn query_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let assets = STATS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (asset, stats) = item?;
+            let liabilities = LIABILITIES.may_load(deps.storage, &asset)?.unwrap_or_default();
+            Ok(AssetStatsResponse {
+                asset,
+                deposited: stats.deposited,
+                withdrawn: stats.withdrawn,
+                deposit_count: stats.deposit_count,
+                beneficiaries: stats.beneficiaries,
+                liabilities,
+            })
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(StatsResponse { assets })
+}
+
+fn query_claim_token(deps: Deps, asset: Option<String>) -> StdResult<ClaimTokenResponse> {
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    let token = CLAIM_TOKENS.may_load(deps.storage, &asset)?.map(Addr::into_string);
+    Ok(ClaimTokenResponse { asset, token })
+}
+
+/// token_view returns the asset shown through the cw20 queries, defaulting to the primary asset.
+fn token_view(deps: Deps) -> StdResult<TokenView> {
+    match TOKEN_VIEW.may_load(deps.storage)? {
+        Some(view) => Ok(view),
+        None => {
+            let asset = asset_key(&resolve_asset(deps, None)?);
+            Ok(TokenView {
+                name: asset.clone(),
+                symbol: asset.clone(),
+                asset,
+                decimals: DEFAULT_TOKEN_DECIMALS,
+            })
+        }
+    }
+}
+
+fn query_balance(deps: Deps, address: String) -> StdResult<BalanceResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let asset = token_view(deps)?.asset;
+    let balance = POTS
+        .may_load(deps.storage, (address.as_str(), &asset))?
+        .map(|pot| pot.collected)
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+fn query_token_info(deps: Deps) -> StdResult<cw20::TokenInfoResponse> {
+    let view = token_view(deps)?;
+    let total_supply = POT_TOTALS.may_load(deps.storage, &view.asset)?.unwrap_or_default();
+    Ok(cw20::TokenInfoResponse {
+        name: view.name,
+        symbol: view.symbol,
+        decimals: view.decimals,
+        total_supply,
+    })
+}
+
+fn query_all_accounts(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllAccountsResponse> {
+    let asset = token_view(deps)?.asset;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let accounts = POT_HOLDERS
+        .prefix(&asset)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<_>>()?;
+    Ok(AllAccountsResponse { accounts })
+}
+
+/// query_asset_balance returns how much of asset the contract holds.
+fn query_asset_balance(deps: Deps, env: &Env, asset: &Denom) -> StdResult<Uint128> {
+    match asset {
+        Denom::Native(denom) => Ok(deps
+            .querier
+            .query_balance(&env.contract.address, denom)?
+            .amount),
+        Denom::Cw20(addr) => {
+            let balance: BalanceResponse = deps.querier.query_wasm_smart(
+                addr,
+                &Cw20QueryMsg::Balance {
+                    address: env.contract.address.to_string(),
+                },
+            )?;
+            Ok(balance.balance)
+        }
+    }
+}
+
+fn deposit_info(id: u64, deposit: Deposit) -> DepositInfo {
+    DepositInfo {
+        id,
+        depositor: deposit.depositor.into_string(),
+        recipient: deposit.recipient.into_string(),
+        amount: deposit.amount,
+        asset: deposit.asset,
+        height: deposit.height,
+        time: deposit.time,
+    }
+}
+
+fn query_withdraw_allowance(
+    deps: Deps,
+    owner: String,
+    spender: String,
+    asset: Option<String>,
+) -> StdResult<AllowanceResponse> {
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let asset = asset_key(&resolve_asset(deps, asset)?);
+    let allowance = WITHDRAW_ALLOWANCES
+        .may_load(deps.storage, (owner_addr.as_str(), spender_addr.as_str(), &asset))?
+        .unwrap_or_default();
+    Ok(allowance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use std::str::FromStr;
+    use cosmwasm_std::{from_binary, Addr, Attribute, ContractResult, ReplyOn, SystemError, SystemResult};
+    use crate::msg::ReceiveMsg;
+
+    /// split_deposit is a plain CreatePot between two targets, paid in native coins or by receive_msg.
+    fn split_deposit(target_1: &str, target_2: &str, receive_msg: Option<Cw20ReceiveMsg>) -> ExecuteMsg {
+        ExecuteMsg::CreatePot {
+            target_addr_1: Some(target_1.to_string()),
+            target_addr_2: Some(target_2.to_string()),
+            template: None,
+            on_reject: RejectFallback::Fail,
+            mint_claims: false,
+            receive_msg,
+        }
+    }
+
+    /// cw20_send is what a cw20 Send of amount from sender hands to the contract.
+    fn cw20_send(sender: &str, amount: u128) -> Cw20ReceiveMsg {
+        Cw20ReceiveMsg { sender: sender.to_string(), amount: Uint128::new(amount), msg: Binary::default() }
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut info = mock_info("creator", &[]);
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // should create pot
+        let msg = split_deposit("alice", "bob", Some(cw20_send("cw20", 100)));
+        info.sender = Addr::unchecked("usei");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "alice".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("alice"),
+                collected: Uint128::new(50)
+            }
+        );
+
+        // Withdraw pot
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(25), asset: None };
+        info.sender = Addr::unchecked("alice");
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "alice".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("alice"),
+                collected: Uint128::new(25)
+            }
+        );
+
+        // Withdraw pot
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(45), asset: None };
+        let info = mock_info("bob", &[]);
+        // info.sender = Addr::unchecked("bob");
+        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "bob".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("bob"),
+                collected: Uint128::new(5)
+            }
+        );
+    }
+
+    #[test]
+    fn test_create_pot() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut info = mock_info("creator", &[]);
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // should create pot
+        let msg = split_deposit("alice", "bob", Some(cw20_send("cw20", 100)));
+        info.sender = Addr::unchecked("usei");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // should create pot
+        let msg = split_deposit("max", "jane", Some(cw20_send("cw20", 100)));
+        info.sender = Addr::unchecked("usei");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // should create pot
+        let msg = split_deposit("karren", "john", Some(cw20_send("cw20", 100)));
+        info.sender = Addr::unchecked("usei");
+        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "alice".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("alice"),
+                collected: Uint128::new(50)
+            }
+        );
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "bob".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("bob"),
+                collected: Uint128::new(50)
+            }
+        );
+
+        // query pot
+        let msg = QueryMsg::GetPot { addr: "karren".to_string(), asset: None };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            Pot {
+                target_addr: Addr::unchecked("karren"),
+                collected: Uint128::new(50)
+            }
+        );
+    }
+
+    #[test]
+    fn test_withdraw_from() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let mut info = mock_info("creator", &[]);
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("cw20", 100)));
+        info.sender = Addr::unchecked("usei");
+        let _res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // operator has no allowance yet
+        let msg = ExecuteMsg::WithdrawFrom {
+            owner: String::from("alice"),
+            recipient: String::from("treasury"),
+            amount: Uint128::new(10),
+            asset: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("operator", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoAllowance {}));
+
+        // alice cannot approve herself
+        let approve = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: String::from("alice"),
+            amount: Uint128::new(30),
+            expires: None,
+            asset: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), approve).unwrap_err();
+        assert!(matches!(err, ContractError::CannotSetOwnAccount {}));
+
+        let approve = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(30),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), approve).unwrap();
+
+        // operator withdraws on alice's behalf to the treasury
+        let res = execute(deps.as_mut(), env.clone(), mock_info("operator", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            Cw20Contract(Addr::unchecked("usei"))
+                .call(Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("treasury"),
+                    amount: Uint128::new(10),
+                })
+                .unwrap()
+        );
+
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::WithdrawAllowance {
+                owner: "alice".to_string(),
+                spender: "operator".to_string(),
+                asset: None,
+            },
+        ).unwrap();
+        let allowance: AllowanceResponse = from_binary(&res).unwrap();
+        assert_eq!(allowance.allowance, Uint128::new(20));
+
+        let msg = QueryMsg::GetPot { addr: "alice".to_string(), asset: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(pot.collected, Uint128::new(40));
+
+        // allowance is checked before the pot balance
+        let msg = ExecuteMsg::WithdrawFrom {
+            owner: String::from("alice"),
+            recipient: String::from("treasury"),
+            amount: Uint128::new(25),
+            asset: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("operator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoAllowance {}));
+
+        // decreasing below zero removes the allowance
+        let decrease = ExecuteMsg::DecreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(50),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), decrease).unwrap();
+        let res = query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::WithdrawAllowance {
+                owner: "alice".to_string(),
+                spender: "operator".to_string(),
+                asset: None,
+            },
+        ).unwrap();
+        let allowance: AllowanceResponse = from_binary(&res).unwrap();
+        assert_eq!(allowance, AllowanceResponse::default());
+
+        // expired allowances cannot be used
+        let approve = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(30),
+            expires: Some(Expiration::AtHeight(env.block.height + 1)),
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), approve).unwrap();
+        let mut later = mock_env();
+        later.block.height += 1;
+        let msg = ExecuteMsg::WithdrawFrom {
+            owner: String::from("alice"),
+            recipient: String::from("treasury"),
+            amount: Uint128::new(5),
+            asset: None,
+        };
+        let err = execute(deps.as_mut(), later, mock_info("operator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Expired {}));
+    }
+
+    #[test]
+    fn test_pause() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let deposit = split_deposit("alice", "bob", Some(cw20_send("cw20", 100)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), deposit.clone()).unwrap();
+        let allow = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(10),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), allow).unwrap();
+
+        // only owners and pausers can pause
+        let pause = ExecuteMsg::SetPause { deposits: true, withdrawals: false };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), pause.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let grant = ExecuteMsg::GrantRole { role: Role::Pauser, addr: String::from("guardian") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("guardian", &[]), pause).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::PauseState {}).unwrap();
+        let state: PauseState = from_binary(&res).unwrap();
+        assert_eq!(
+            state,
+            PauseState {
+                deposits: true,
+                withdrawals: false,
+                set_by: Some(Addr::unchecked("guardian")),
+            }
+        );
+
+        // deposits are frozen, withdrawals still work
+        let err = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), deposit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "deposits"));
+        let withdraw = ExecuteMsg::WithdrawPot { amount: Uint128::new(10), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), withdraw.clone()).unwrap();
+
+        // freeze withdrawals instead
+        let pause = ExecuteMsg::SetPause { deposits: false, withdrawals: true };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), withdraw).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "withdrawals"));
+        let withdraw_from = ExecuteMsg::WithdrawFrom {
+            owner: String::from("alice"),
+            recipient: String::from("treasury"),
+            amount: Uint128::new(10),
+            asset: None,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("operator", &[]), withdraw_from).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "withdrawals"));
+        let sweep = ExecuteMsg::SweepSurplus {
+            asset: Denom::Cw20(Addr::unchecked("usei")),
+            recipient: String::from("treasury"),
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), sweep).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "withdrawals"));
+        // revoking an allowance only lowers the risk and stays available
+        let revoke = ExecuteMsg::DecreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(10),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), revoke).unwrap();
+        let allowance = query_withdraw_allowance(deps.as_ref(), "alice".to_string(), "operator".to_string(), None).unwrap();
+        assert_eq!(allowance.allowance, Uint128::zero());
+        let _res = execute(deps.as_mut(), env, mock_info("usei", &[]), deposit).unwrap();
+    }
+
+    #[test]
+    fn test_roles() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Roles { addr: "creator".to_string() }).unwrap();
+        let roles: RolesResponse = from_binary(&res).unwrap();
+        assert_eq!(roles.roles, vec![Role::Owner]);
+
+        // only owners can grant roles
+        let grant = ExecuteMsg::GrantRole { role: Role::Operator, addr: String::from("ops") };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("ops", &[]), grant.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        let grant = ExecuteMsg::GrantRole { role: Role::Pauser, addr: String::from("ops") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        let grant = ExecuteMsg::GrantRole { role: Role::Pauser, addr: String::from("guardian") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Roles { addr: "ops".to_string() }).unwrap();
+        let roles: RolesResponse = from_binary(&res).unwrap();
+        assert_eq!(roles.roles, vec![Role::Pauser, Role::Operator]);
+
+        let msg = QueryMsg::RoleMembers { role: Role::Pauser, start_after: None, limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let members: RoleMembersResponse = from_binary(&res).unwrap();
+        assert_eq!(members.members, vec!["guardian".to_string(), "ops".to_string()]);
+        let msg = QueryMsg::RoleMembers { role: Role::Pauser, start_after: Some("guardian".to_string()), limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let members: RoleMembersResponse = from_binary(&res).unwrap();
+        assert_eq!(members.members, vec!["ops".to_string()]);
+
+        // the last owner cannot be revoked
+        let revoke = ExecuteMsg::RevokeRole { role: Role::Owner, addr: String::from("creator") };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), revoke.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::LastOwner {}));
+
+        // hand ownership over to a second owner
+        let grant = ExecuteMsg::GrantRole { role: Role::Owner, addr: String::from("new_owner") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        assert_eq!(query_owner(deps.as_ref()).unwrap().owner, "creator");
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("new_owner", &[]), revoke).unwrap();
+        assert_eq!(query_owner(deps.as_ref()).unwrap().owner, "new_owner");
+        let grant = ExecuteMsg::GrantRole { role: Role::Pauser, addr: String::from("creator") };
+        let err = execute(deps.as_mut(), env, mock_info("creator", &[]), grant).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn test_deposit_history() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        for (sender, target_1, target_2, amount) in [
+            ("max", "alice", "bob", 100u128),
+            ("jane", "alice", "karren", 40),
+            ("max", "john", "karren", 10),
+        ] {
+            env.block.height += 1;
+            let msg = split_deposit(target_1, target_2, Some(cw20_send(sender, amount)));
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        }
+
+        let msg = QueryMsg::DepositsBySender { sender: "max".to_string(), start_after: None, limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let history: DepositsResponse = from_binary(&res).unwrap();
+        let ids: Vec<u64> = history.deposits.iter().map(|d| d.id).collect();
+        assert_eq!(ids, vec![1, 2, 5, 6]);
+        assert_eq!(
+            history.deposits[2],
+            DepositInfo {
+                id: 5,
+                depositor: "max".to_string(),
+                recipient: "john".to_string(),
+                amount: Uint128::new(5),
+                asset: "usei".to_string(),
+                height: mock_env().block.height + 3,
+                time: env.block.time,
+            }
+        );
+
+        let msg = QueryMsg::DepositsByRecipient { recipient: "alice".to_string(), start_after: None, limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let history: DepositsResponse = from_binary(&res).unwrap();
+        let senders: Vec<(&str, Uint128)> = history
+            .deposits
+            .iter()
+            .map(|d| (d.depositor.as_str(), d.amount))
+            .collect();
+        assert_eq!(senders, vec![("max", Uint128::new(50)), ("jane", Uint128::new(20))]);
+
+        // paginate
+        let msg = QueryMsg::DepositsByRecipient { recipient: "karren".to_string(), start_after: Some(4), limit: Some(1) };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let history: DepositsResponse = from_binary(&res).unwrap();
+        assert_eq!(history.deposits.len(), 1);
+        assert_eq!(history.deposits[0].id, 6);
+    }
+
+    #[test]
+    fn test_statement() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        for (sender, amount) in [("max", 100u128), ("jane", 60)] {
+            let msg = split_deposit("alice", "bob", Some(cw20_send(sender, amount)));
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        }
+
+        // credits accumulate instead of overwriting the pot
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPot { addr: "alice".to_string(), asset: None }).unwrap();
+        let pot: Pot = from_binary(&res).unwrap();
+        assert_eq!(pot.collected, Uint128::new(80));
+
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(30), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        let approve = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: String::from("operator"),
+            amount: Uint128::new(50),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), approve).unwrap();
+        let msg = ExecuteMsg::WithdrawFrom {
+            owner: String::from("alice"),
+            recipient: String::from("treasury"),
+            amount: Uint128::new(50),
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("operator", &[]), msg).unwrap();
+
+        let msg = QueryMsg::Statement { addr: "alice".to_string(), start_after: None, limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let statement: StatementResponse = from_binary(&res).unwrap();
+        let lines: Vec<(LedgerKind, &str, u128, u128)> = statement
+            .entries
+            .iter()
+            .map(|e| (e.kind, e.counterparty.as_str(), e.amount.u128(), e.balance.u128()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (LedgerKind::Deposit, "max", 50, 50),
+                (LedgerKind::Deposit, "jane", 30, 80),
+                (LedgerKind::Withdrawal, "alice", 30, 50),
+                (LedgerKind::Withdrawal, "treasury", 50, 0),
+            ]
+        );
+
+        // bob's entries are interleaved in the global sequence but not in alice's statement
+        let start_after = statement.entries[1].id;
+        let msg = QueryMsg::Statement { addr: "alice".to_string(), start_after: Some(start_after), limit: Some(1) };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let statement: StatementResponse = from_binary(&res).unwrap();
+        assert_eq!(statement.entries.len(), 1);
+        assert_eq!(statement.entries[0].kind, LedgerKind::Withdrawal);
+        assert_eq!(statement.entries[0].balance, Uint128::new(50));
+    }
+
+    #[test]
+    fn test_solvency() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // nothing owed yet
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_binary(&res).unwrap();
+        assert_eq!(solvency, SolvencyResponse { assets: vec![], solvent: true });
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 101)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(20), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+
+        // the odd token left over by the split is surplus, not a liability
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "usei" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&BalanceResponse { balance: Uint128::new(81) }).unwrap(),
+                ))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            solvency,
+            SolvencyResponse {
+                assets: vec![AssetSolvency {
+                    asset: "usei".to_string(),
+                    liabilities: Uint128::new(80),
+                    balance: Uint128::new(81),
+                    solvent: true,
+                }],
+                solvent: true,
+            }
+        );
+
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&BalanceResponse { balance: Uint128::new(79) }).unwrap(),
+            ))
+        });
+        let res = query(deps.as_ref(), env, QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_binary(&res).unwrap();
+        assert!(!solvency.solvent);
+        assert!(!solvency.assets[0].solvent);
+    }
+
+    #[test]
+    fn test_sweep_surplus() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 100)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+
+        let sweep = ExecuteMsg::SweepSurplus {
+            asset: Denom::Cw20(Addr::unchecked("usei")),
+            recipient: String::from("treasury"),
+        };
+
+        // the contract holds exactly what it owes
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&BalanceResponse { balance: Uint128::new(100) }).unwrap(),
+            ))
+        });
+        let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), sweep.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoSurplus {}));
+
+        // 15 usei were sent directly to the contract
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_binary(&BalanceResponse { balance: Uint128::new(115) }).unwrap(),
+            ))
+        });
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), sweep.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), sweep).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            Cw20Contract(Addr::unchecked("usei"))
+                .call(Cw20ExecuteMsg::Transfer {
+                    recipient: String::from("treasury"),
+                    amount: Uint128::new(15),
+                })
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        for (target_1, target_2, amount) in [("alice", "bob", 100u128), ("alice", "karren", 41)] {
+            let msg = split_deposit(target_1, target_2, Some(cw20_send("max", amount)));
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        }
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(30), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        // transfers move existing balances and do not add beneficiaries
+        let msg = ExecuteMsg::TransferBalance { to: "dave".to_string(), amount: Uint128::new(5), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+
+        let res = query(deps.as_ref(), env, QueryMsg::Stats {}).unwrap();
+        let stats: StatsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            stats.assets,
+            vec![AssetStatsResponse {
+                asset: "usei".to_string(),
+                deposited: Uint128::new(141),
+                withdrawn: Uint128::new(30),
+                deposit_count: 2,
+                beneficiaries: 3,
+                liabilities: Uint128::new(110),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_get_pots() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 100)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(50), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), msg).unwrap();
+
+        let msg = QueryMsg::GetPots {
+            addrs: vec!["alice".to_string(), "bob".to_string(), "nobody".to_string()],
+            asset: None,
+        };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        let pots: PotsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            pots.pots,
+            vec![
+                PotResponse { target_addr: "alice".to_string(), collected: "50".to_string(), asset: "usei".to_string(), has_history: true, locked: "0".to_string() },
+                PotResponse { target_addr: "bob".to_string(), collected: "0".to_string(), asset: "usei".to_string(), has_history: true, locked: "0".to_string() },
+                PotResponse { target_addr: "nobody".to_string(), collected: "0".to_string(), asset: "usei".to_string(), has_history: false, locked: "0".to_string() },
+            ]
+        );
+
+        // batches are bounded like every other list query
+        let addrs: Vec<String> = (0..=MAX_LIMIT).map(|i| format!("addr{}", i)).collect();
+        let _res = query_pots(deps.as_ref(), addrs[1..].to_vec(), None).unwrap();
+        let _err = query_pots(deps.as_ref(), addrs, None).unwrap_err();
+    }
+
+    #[test]
+    fn test_get_account() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // never funded
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPot { addr: "alice".to_string(), asset: None }).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            PotResponse { target_addr: "alice".to_string(), collected: "0".to_string(), asset: "usei".to_string(), has_history: false, locked: "0".to_string() }
+        );
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 100)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(50), asset: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+
+        // fully withdrawn
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPot { addr: "alice".to_string(), asset: None }).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            pot,
+            PotResponse { target_addr: "alice".to_string(), collected: "0".to_string(), asset: "usei".to_string(), has_history: true, locked: "0".to_string() }
+        );
+
+        let res = query(deps.as_ref(), env, QueryMsg::GetAccount { addr: "alice".to_string(), asset: None }).unwrap();
+        let account: AccountResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            account,
+            AccountResponse {
+                addr: "alice".to_string(),
+                asset: "usei".to_string(),
+                balance: Uint128::zero(),
+                locked: Uint128::zero(),
+                received: Uint128::new(50),
+                withdrawn: Uint128::new(50),
+                transferred: Uint128::zero(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_accepted_assets() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        // at least one asset is required, duplicates are dropped and denoms cannot be empty
+        let msg = InstantiateMsg { owner: None, native_denoms: vec![], cw20_tokens: vec![] };
+        let err = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoAcceptedAsset {}));
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["".to_string()], cw20_tokens: vec![] };
+        let _err = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec!["uatom".to_string(), "uatom".to_string()],
+            cw20_tokens: vec!["usei".to_string(), "usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!((config.native_denoms.len(), config.cw20_tokens.len()), (1, 1));
+
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec!["uatom".to_string()],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+        // native deposits come from the sender's funds
+        let native_deposit = split_deposit("alice", "bob", None);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), native_deposit.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "ujuno")), native_deposit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedAsset { asset } if asset == "ujuno"));
+
+        // cw20 deposits come from an accepted token contract
+        let cw20_deposit = split_deposit("alice", "bob", Some(cw20_send("max", 40)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), cw20_deposit.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fake", &[]), cw20_deposit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedAsset { asset } if asset == "fake"));
+
+        // balances are kept per asset, the native denom being the primary one
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::GetPot { addr: "alice".to_string(), asset: None }).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!((pot.asset.as_str(), pot.collected.as_str()), ("uatom", "50"));
+        let msg = QueryMsg::GetPot { addr: "alice".to_string(), asset: Some("usei".to_string()) };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let pot: PotResponse = from_binary(&res).unwrap();
+        assert_eq!((pot.asset.as_str(), pot.collected.as_str()), ("usei", "20"));
+
+        // operators manage the accepted assets
+        let add = ExecuteMsg::AddAcceptedAsset { asset: Denom::Native("ujuno".to_string()) };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("ops", &[]), add.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let grant = ExecuteMsg::GrantRole { role: Role::Operator, addr: String::from("ops") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("ops", &[]), add).unwrap();
+        // the primary asset backs every message that leaves the asset out, so it stays accepted
+        let remove = |asset| ExecuteMsg::RemoveAcceptedAsset { asset };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("ops", &[]), remove(Denom::Native("uatom".to_string()))).unwrap_err();
+        assert!(matches!(err, ContractError::PrimaryAsset { asset } if asset == "uatom"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("ops", &[]), remove(Denom::Cw20(Addr::unchecked("usei")))).unwrap();
+
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(config.native_denoms, vec!["uatom".to_string(), "ujuno".to_string()]);
+        assert_eq!(config.cw20_tokens, Vec::<String>::new());
+        assert_eq!(config.primary_asset, "uatom");
+
+        // a removed asset takes no new deposits but existing balances can still be withdrawn
+        let err = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), cw20_deposit).unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedAsset { asset } if asset == "usei"));
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(20), asset: Some("usei".to_string()) };
+        let res = execute(deps.as_mut(), env, mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            Cw20Contract(Addr::unchecked("usei"))
+                .call(Cw20ExecuteMsg::Transfer { recipient: String::from("alice"), amount: Uint128::new(20) })
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_events() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec![], cw20_tokens: vec!["usei".to_string()] };
+        let res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.events, vec![Event::new("owner_changed")
+            .add_attribute("owner", "creator")
+            .add_attribute("change", "added")]);
+
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 100)));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        assert_eq!(res.events.len(), 2);
+        assert_eq!(res.events[1], Event::new("pot_deposit")
+            .add_attribute("deposit_id", "2")
+            .add_attribute("depositor", "max")
+            .add_attribute("recipient", "bob")
+            .add_attribute("asset", "usei")
+            .add_attribute("amount", "50")
+            .add_attribute("balance", "50"));
+
+        // the spender only shows up on allowance withdrawals
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(20), asset: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(res.events, vec![Event::new("pot_withdraw")
+            .add_attribute("owner", "alice")
+            .add_attribute("recipient", "alice")
+            .add_attribute("asset", "usei")
+            .add_attribute("amount", "20")
+            .add_attribute("balance", "30")]);
+
+        let msg = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: "carol".to_string(),
+            amount: Uint128::new(10),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        let msg = ExecuteMsg::WithdrawFrom {
+            owner: "alice".to_string(),
+            recipient: "dave".to_string(),
+            amount: Uint128::new(10),
+            asset: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), msg).unwrap();
+        assert_eq!(res.events[0].attributes[2], Attribute::new("spender", "carol"));
+        assert_eq!(res.events[0].attributes[5], Attribute::new("balance", "20"));
+
+        // owner changes are reported once, on actual grants and revocations
+        let grant = ExecuteMsg::GrantRole { role: Role::Owner, addr: "erin".to_string() };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant.clone()).unwrap();
+        assert_eq!(res.events[0].ty, "owner_changed");
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), grant).unwrap();
+        assert!(res.events.is_empty());
+        let msg = ExecuteMsg::RevokeRole { role: Role::Owner, addr: "erin".to_string() };
+        let res = execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.events, vec![Event::new("owner_changed")
+            .add_attribute("owner", "erin")
+            .add_attribute("change", "removed")]);
+    }
+
+    #[test]
+    fn test_withdraw_to_contract() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec![], cw20_tokens: vec!["usei".to_string()] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let msg = split_deposit("alice", "bob", Some(cw20_send("max", 100)));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), msg).unwrap();
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == "vault" => SystemResult::Ok(
+                ContractResult::Ok(to_binary(&WasmContractInfoResponse::new(1, "creator")).unwrap()),
+            ),
+            WasmQuery::ContractInfo { contract_addr } => {
+                SystemResult::Err(SystemError::NoSuchContract { addr: contract_addr.clone() })
+            }
+            _ => panic!("unexpected query"),
+        });
+
+        // plain addresses keep a fire-and-forget transfer
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(10), asset: None };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Never);
+
+        // contracts always get a reply, and a successful one clears the pending withdrawal
+        let msg = ExecuteMsg::IncreaseWithdrawAllowance {
+            spender: "carol".to_string(),
+            amount: Uint128::new(30),
+            expires: None,
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        let withdraw_from = |amount| ExecuteMsg::WithdrawFrom {
+            owner: "alice".to_string(),
+            recipient: "vault".to_string(),
+            amount: Uint128::new(amount),
+            asset: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), withdraw_from(5)).unwrap();
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+        assert_eq!(res.messages[0].id, WITHDRAW_REPLY_ID);
+        let reply_msg = Reply {
+            id: WITHDRAW_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse { events: vec![], data: None }),
+        };
+        let res = reply(deps.as_mut(), env.clone(), reply_msg).unwrap();
+        assert_eq!(res.events.len(), 0);
+        assert_eq!(PENDING_WITHDRAWAL.may_load(&deps.storage).unwrap(), None);
+
+        // a failure after a success undoes its own withdrawal only
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), withdraw_from(25)).unwrap();
+        assert_eq!(query_pot(deps.as_ref(), "alice", None).unwrap().collected, "10");
 
-    Ok(Response::new()
-        .add_attribute("method", "instantiate")
-        .add_attribute("owner", owner))
-}
+        let reply_msg = Reply { id: WITHDRAW_REPLY_ID, result: SubMsgResult::Err("rejected".to_string()) };
+        let res = reply(deps.as_mut(), env.clone(), reply_msg).unwrap();
+        assert_eq!(res.events, vec![Event::new("pot_withdraw_failed")
+            .add_attribute("owner", "alice")
+            .add_attribute("recipient", "vault")
+            .add_attribute("asset", "usei")
+            .add_attribute("amount", "25")
+            .add_attribute("balance", "35")
+            .add_attribute("error", "rejected")]);
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::CreatePot {
-            target_addr_1,
-            target_addr_2,
-            receive_msg,
-        } => execute_create_pot(deps, info, target_addr_1, target_addr_2, receive_msg),
-        ExecuteMsg::WithdrawPot {
-            amount
-        } => execute_withdraw_pot(deps, info, amount),
-    }
-}
+        // everything the failed withdrawal moved is back
+        assert_eq!(query_pot(deps.as_ref(), "alice", None).unwrap().collected, "35");
+        let allowance = query_withdraw_allowance(deps.as_ref(), "alice".to_string(), "carol".to_string(), None).unwrap();
+        assert_eq!(allowance.allowance, Uint128::new(25));
+        let account = query_account(deps.as_ref(), "alice".to_string(), None).unwrap();
+        assert_eq!((account.received, account.withdrawn), (Uint128::new(50), Uint128::new(15)));
+        assert_eq!(LIABILITIES.load(&deps.storage, "usei").unwrap(), Uint128::new(85));
+        let statement = query_statement(deps.as_ref(), "alice".to_string(), None, None).unwrap();
+        assert_eq!(statement.entries.last().unwrap().kind, LedgerKind::FailedWithdrawal);
+        assert_eq!(PENDING_WITHDRAWAL.may_load(&deps.storage).unwrap(), None);
 
-pub fn execute_withdraw_pot(
-    deps: DepsMut,
-    info: MessageInfo,
-    amount: Uint128,
-) -> Result<Response, ContractError> {
-    // address that requested the withdrawl
-    let address_request = info.sender;
-    let str_address_request = address_request.to_string();
-    // Find the address in POTS. Error if not found.
-    let mut p = POTS.load(deps.storage, address_request.as_str())?;
-    // Verify the amount is correct
-    if amount > p.collected {
-        return Err(ContractError::CustomError { val:"Wrong amount to withdraw".to_string() });
-    }
-    // Making sure address_request is equal to target address in pot.
-    if address_request != p.target_addr {
-        // This check is quite useless
-        return Err(ContractError::CustomError { val:"Wrong address to withdraw".to_string() });
+        let reply_msg = Reply { id: 7, result: SubMsgResult::Err("rejected".to_string()) };
+        let err = reply(deps.as_mut(), env, reply_msg).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownReplyId { id: 7 }));
     }
 
-    let mut res = Response::new()
-        .add_attribute("action", "withdraw")
-        .add_attribute("address", p.target_addr.to_string());
+    #[test]
+    fn test_escrow() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
-    // let cw20_addr = address_request;
-    let cw20 = Cw20Contract(deps.api.addr_validate("usei")?);
-    let msg = cw20.call(Cw20ExecuteMsg::Transfer {
-        recipient: address_request.into_string(),
-        amount: amount,
-    })?;
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec!["uatom".to_string()],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let create = ExecuteMsg::CreateEscrowPot {
+            target_addr_1: String::from("alice"),
+            target_addr_2: String::from("bob"),
+            arbiter: String::from("judge"),
+            timeout: Some(Expiration::AtHeight(env.block.height + 10)),
+            milestones: vec![],
+            receive_msg: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), create.clone()).unwrap();
+        let event = &res.events[0];
+        assert_eq!(event.ty, "escrow_created");
+        assert_eq!(event.attributes[0], Attribute::new("escrow_id", "1"));
+        assert_eq!(event.attributes[7], Attribute::new("locked", "50"));
 
-    res = res.add_message(msg);
+        // locked funds are reported apart and cannot be withdrawn
+        let pot = query_pot(deps.as_ref(), "alice", None).unwrap();
+        assert_eq!((pot.collected.as_str(), pot.locked.as_str()), ("0", "50"));
+        let account = query_account(deps.as_ref(), "alice".to_string(), None).unwrap();
+        assert_eq!((account.balance, account.locked), (Uint128::zero(), Uint128::new(50)));
+        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(50), asset: None };
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap_err();
 
-    POTS.remove(deps.storage, &str_address_request);
-    if amount < p.collected {
-        p.collected = p.collected - amount;
-        POTS.save(deps.storage, p.target_addr.as_str(), &p);
-    }
-    Ok(res)
-}
+        // only the arbiter releases, once, and not while deposits are paused
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &[]), ExecuteMsg::Release { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let pause = |deposits| ExecuteMsg::SetPause { deposits, withdrawals: false };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(true)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), ExecuteMsg::Release { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "deposits"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(false)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), ExecuteMsg::Release { id: 1 }).unwrap();
+        assert_eq!(res.events.iter().filter(|e| e.ty == "pot_deposit").count(), 2);
+        let pot = query_pot(deps.as_ref(), "alice", None).unwrap();
+        assert_eq!((pot.collected.as_str(), pot.locked.as_str()), ("50", "0"));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), ExecuteMsg::Refund { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::EscrowClosed { id: 1 }));
 
-pub fn execute_create_pot(
-    deps: DepsMut,
-    info: MessageInfo,
-    target_addr_1: String,
-    target_addr_2: String,
-    wrapped: Cw20ReceiveMsg,
-) -> Result<Response, ContractError> {
+        // the depositor can only refund itself after the timeout
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), create).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &[]), ExecuteMsg::Refund { id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let mut later = env.clone();
+        later.block.height += 10;
+        let res = execute(deps.as_mut(), later, mock_info("max", &[]), ExecuteMsg::Refund { id: 2 }).unwrap();
+        assert_eq!(res.events, vec![Event::new("pot_refund")
+            .add_attribute("escrow_id", "2")
+            .add_attribute("depositor", "max")
+            .add_attribute("asset", "uatom")
+            .add_attribute("amount", "100")
+            .add_attribute("balance", "100")]);
 
-    // This only works for usei token
-    if info.sender != "usei" {
-        return Err(ContractError::CustomError { val:"Wrong token".to_string() });
-    }
-    if wrapped.amount == Uint128::new(0) {
-        return Err(ContractError::CustomError { val:"No token sent".to_string() });
+        let escrow = query_escrow(deps.as_ref(), 2).unwrap();
+        assert_eq!(escrow.status, EscrowStatus::Refunded);
+        let pot = query_pot(deps.as_ref(), "alice", None).unwrap();
+        assert_eq!((pot.collected.as_str(), pot.locked.as_str()), ("50", "0"));
+        let statement = query_statement(deps.as_ref(), "max".to_string(), None, None).unwrap();
+        assert_eq!(statement.entries[0].kind, LedgerKind::Refund);
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(200));
+        let stats = STATS.load(&deps.storage, "uatom").unwrap();
+        assert_eq!((stats.deposited, stats.deposit_count), (Uint128::new(200), 2));
+
+        // a cw20 Send to this contract opens an escrow in that token
+        let hook = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("max"),
+            amount: Uint128::new(30),
+            msg: to_binary(&ReceiveMsg::CreateEscrowPot {
+                target_addr_1: String::from("alice"),
+                target_addr_2: String::from("bob"),
+                arbiter: String::from("judge"),
+                timeout: None,
+                milestones: vec![],
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), hook).unwrap();
+        let escrow = query_escrow(deps.as_ref(), 3).unwrap();
+        assert_eq!((escrow.depositor.as_str(), escrow.asset.as_str(), escrow.share), ("max", "usei", Uint128::new(15)));
+        let pot = query_pot(deps.as_ref(), "bob", Some("usei".to_string())).unwrap();
+        assert_eq!(pot.locked, "15");
+
+        let msg = ExecuteMsg::CreateEscrowPot {
+            target_addr_1: String::from("alice"),
+            target_addr_2: String::from("bob"),
+            arbiter: String::from("judge"),
+            timeout: Some(Expiration::AtHeight(env.block.height)),
+            milestones: vec![],
+            receive_msg: None,
+        };
+        let err = execute(deps.as_mut(), env, mock_info("max", &coins(100, "uatom")), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidExpiration {}));
     }
 
-    let amount_for_each_pot = wrapped.amount.div(Uint128::new(2));
-    let pot1 = Pot {
-        target_addr: deps.api.addr_validate(target_addr_1.as_str())?,
-        collected: amount_for_each_pot,
-    };
-    let pot2 = Pot {
-        target_addr: deps.api.addr_validate(target_addr_2.as_str())?,
-        collected: amount_for_each_pot,
-    };
+    #[test]
+    fn test_milestones() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
-    save_pot(deps, &pot1, &pot2)?;
-    Ok(Response::new()
-        .add_attribute("action", "execute_create_pot")
-        .add_attribute("target_addr_1", target_addr_1)
-        .add_attribute("target_addr_2", target_addr_2)
-    )
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let milestone = |description: &str, share: &str| MilestoneMsg {
+            description: description.to_string(),
+            share: Decimal::from_str(share).unwrap(),
+        };
+        let create = |milestones| ExecuteMsg::CreateEscrowPot {
+            target_addr_1: String::from("alice"),
+            target_addr_2: String::from("bob"),
+            arbiter: String::from("judge"),
+            timeout: None,
+            milestones,
+            receive_msg: None,
+        };
+        let msg = create(vec![milestone("design", "0.5"), milestone("build", "0.4")]);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(200, "uatom")), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidMilestones {}));
+        let msg = create(vec![milestone("design", "0.3333"), milestone("build", "0.3333"), milestone("ship", "0.3334")]);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(200, "uatom")), msg).unwrap();
 
-}
+        // milestones unlock their tranche in order, the last one taking the rounding
+        let msg = ExecuteMsg::CompleteMilestone { id: 1, milestone: 1 };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::MilestoneOutOfOrder { expected: 0 }));
+        let msg = ExecuteMsg::CompleteMilestone { id: 1, milestone: 0 };
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), msg).unwrap();
+        let pot = query_pot(deps.as_ref(), "bob", None).unwrap();
+        assert_eq!((pot.collected.as_str(), pot.locked.as_str()), ("33", "67"));
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
-        QueryMsg::QueryOwner {} => to_binary(&query_owner(deps)),
-        QueryMsg::GetPot { addr } => to_binary(&query_pot(deps, &addr)?),
+        let milestones = query_milestones(deps.as_ref(), 1).unwrap().milestones;
+        assert_eq!(
+            milestones.iter().map(|m| (m.amount.u128(), m.completed)).collect::<Vec<_>>(),
+            vec![(33, true), (33, false), (34, false)]
+        );
+
+        // a refund returns only what is still locked
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("judge", &[]), ExecuteMsg::Refund { id: 1 }).unwrap();
+        let pot = query_pot(deps.as_ref(), "max", None).unwrap();
+        assert_eq!(pot.collected, "134");
+        let pot = query_pot(deps.as_ref(), "bob", None).unwrap();
+        assert_eq!((pot.collected.as_str(), pot.locked.as_str()), ("33", "0"));
+        let escrow = query_escrow(deps.as_ref(), 1).unwrap();
+        assert_eq!((escrow.status, escrow.released), (EscrowStatus::Refunded, Uint128::new(33)));
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(200));
     }
-}
 
-fn query_owner (deps: Deps) -> Config {
-    let owner = CONFIG.load(deps.storage).unwrap();
-    owner
-}
+    #[test]
+    fn test_joint_pot() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
-fn query_pot(deps: Deps, addr: &str) -> StdResult<PotResponse> {
-    let pot = POTS.load(deps.storage, addr)?;
-    Ok(PotResponse {
-        target_addr: pot.target_addr.into_string(),
-        collected: pot.collected.to_string(),
-    })
-}
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec!["uatom".to_string()],
+            cw20_tokens: vec!["usei".to_string()],
+        };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::CreateJointPot {
+            target_addr_1: String::from("bob"),
+            target_addr_2: String::from("alice"),
+            receive_msg: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), msg).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_binary, Addr};
+        // the whole deposit sits in one pot, out of reach of individual withdrawals
+        let joint = query_joint_pot(deps.as_ref(), "alice".to_string(), "bob".to_string(), None).unwrap();
+        assert_eq!((joint.members, joint.balance), (vec!["alice".to_string(), "bob".to_string()], Uint128::new(100)));
+        assert_eq!(query_pot(deps.as_ref(), "alice", None).unwrap().collected, "0");
+
+        let propose = ExecuteMsg::ProposeJointWithdrawal {
+            partner: "bob".to_string(),
+            recipient: "carol".to_string(),
+            amount: Uint128::new(60),
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), propose.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecuteMsg::ConfirmJointWithdrawal { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), ExecuteMsg::ConfirmJointWithdrawal { id: 1 }).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send { to_address: "carol".to_string(), amount: coins(60, "uatom") })
+        );
+        assert_eq!(res.events[0].ty, "joint_pot_withdraw");
+        let joint = query_joint_pot(deps.as_ref(), "bob".to_string(), "alice".to_string(), None).unwrap();
+        assert_eq!(joint.balance, Uint128::new(40));
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(40));
+        let _err = query_joint_proposal(deps.as_ref(), 1).unwrap_err();
+
+        // both members see the joint pot activity in their statements
+        for member in ["alice", "bob"] {
+            let statement = query_statement(deps.as_ref(), member.to_string(), None, None).unwrap();
+            let lines: Vec<_> = statement
+                .entries
+                .iter()
+                .map(|e| (e.kind, e.counterparty.as_str(), e.amount.u128(), e.balance.u128()))
+                .collect();
+            assert_eq!(lines, vec![(LedgerKind::Deposit, "max", 100, 100), (LedgerKind::Withdrawal, "carol", 60, 40)]);
+        }
+
+        // a cw20 Send to this contract deposits into the joint pot in that token
+        let hook = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("max"),
+            amount: Uint128::new(25),
+            msg: to_binary(&ReceiveMsg::CreateJointPot {
+                target_addr_1: String::from("alice"),
+                target_addr_2: String::from("bob"),
+            })
+            .unwrap(),
+        });
+        let res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), hook).unwrap();
+        assert_eq!(res.events[0].ty, "joint_pot_deposit");
+        let joint = query_joint_pot(deps.as_ref(), "alice".to_string(), "bob".to_string(), Some("usei".to_string())).unwrap();
+        assert_eq!(joint.balance, Uint128::new(25));
+
+        // proposals lapse after the configured ttl
+        let msg = ExecuteMsg::SetProposalTtl { seconds: 60 };
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), propose).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { balance } if balance == "40"));
+        let propose = ExecuteMsg::ProposeJointWithdrawal {
+            partner: "alice".to_string(),
+            recipient: "bob".to_string(),
+            amount: Uint128::new(40),
+            asset: None,
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), propose).unwrap();
+        let proposal = query_joint_proposal(deps.as_ref(), 2).unwrap();
+        assert_eq!(proposal.expires, env.block.time.plus_seconds(60));
+        let mut later = env;
+        later.block.time = later.block.time.plus_seconds(60);
+        let err = execute(deps.as_mut(), later, mock_info("alice", &[]), ExecuteMsg::ConfirmJointWithdrawal { id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::ProposalExpired { id: 2 }));
+    }
 
     #[test]
-    fn test_withdraw() {
+    fn test_campaign() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let mut info = mock_info("creator", &[]);
 
-        let msg = InstantiateMsg { owner: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec!["usei".to_string()] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let create = ExecuteMsg::CreateCampaign {
+            target_addr_1: String::from("alice"),
+            target_addr_2: String::from("bob"),
+            asset: "uatom".to_string(),
+            goal: Uint128::new(100),
+            deadline: Expiration::AtHeight(env.block.height + 10),
+        };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), create.clone()).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), create).unwrap();
+        let mut later = env.clone();
+        later.block.height += 10;
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
+        // campaign 1 reaches its goal and pays out
+        let contribute = |id| ExecuteMsg::Contribute { id, receive_msg: None };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(60, "uatom")), contribute(1)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("jane", &[]), ExecuteMsg::Finalize { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::GoalNotReached { id: 1 }));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("jane", &coins(1, "uatom")), contribute(1)).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("jane", &coins(40, "uatom")), contribute(1)).unwrap();
+        let event = &res.events[0];
+        assert_eq!(event.ty, "campaign_contribution");
+        assert_eq!(event.attributes[4], Attribute::new("contribution", "41"));
+        assert_eq!(event.attributes[5], Attribute::new("raised", "101"));
+        let campaign = query_campaign(deps.as_ref(), env.clone(), 1).unwrap();
+        assert_eq!((campaign.raised, campaign.contributors), (Uint128::new(101), 2));
+        assert_eq!(campaign.progress, Decimal::from_str("1.01").unwrap());
+        let _res = execute(deps.as_mut(), later.clone(), mock_info("jane", &[]), ExecuteMsg::Finalize { id: 1 }).unwrap();
+        assert_eq!(query_pot(deps.as_ref(), "alice", None).unwrap().collected, "50");
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(5, "uatom")), contribute(1)).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignClosed { id: 1 }));
+        let err = execute(deps.as_mut(), later.clone(), mock_info("max", &[]), ExecuteMsg::ClaimRefund { id: 1 }).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignNotFailed { id: 1 }));
+
+        // cw20 tokens are contributed through the receive hook, to campaigns in that token only
+        let hook = |id, amount| ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("max"),
+            amount: Uint128::new(amount),
+            msg: to_binary(&ReceiveMsg::Contribute { id }).unwrap(),
+        });
+        let err = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), hook(2, 40)).unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedAsset { asset } if asset == "usei"));
+        let create = |goal| ExecuteMsg::CreateCampaign {
             target_addr_1: String::from("alice"),
             target_addr_2: String::from("bob"),
-            receive_msg: Cw20ReceiveMsg {
-                sender: String::from("cw20"),
-                amount: Uint128::new(100),
-                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
-            }
+            asset: "usei".to_string(),
+            goal: Uint128::new(goal),
+            deadline: Expiration::AtHeight(env.block.height + 10),
         };
-        info.sender = Addr::unchecked("usei");
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), create(100)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), hook(3, 40)).unwrap();
+        assert_eq!(query_contribution(deps.as_ref(), 3, "max".to_string()).unwrap().amount, Uint128::new(40));
+        // progress saturates rather than failing the query when it outgrows Decimal
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), create(1)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), hook(4, 10u128.pow(21))).unwrap();
+        assert_eq!(query_campaign(deps.as_ref(), env.clone(), 4).unwrap().progress, Decimal::MAX);
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "alice".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
-        assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("alice"),
-                collected: Uint128::new(50)
-            }
-        );
+        // campaign 2 misses its goal and refunds each contributor exactly
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(30, "uatom")), contribute(2)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(10, "uatom")), contribute(2)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("jane", &coins(20, "uatom")), contribute(2)).unwrap();
+        let err = execute(deps.as_mut(), later.clone(), mock_info("max", &coins(50, "uatom")), contribute(2)).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignClosed { id: 2 }));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &[]), ExecuteMsg::ClaimRefund { id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::CampaignNotFailed { id: 2 }));
 
-        // Withdraw pot
-        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(25) };
-        info.sender = Addr::unchecked("alice");
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(query_campaign(deps.as_ref(), later.clone(), 2).unwrap().failed);
+        let pause = |withdrawals| ExecuteMsg::SetPause { deposits: false, withdrawals };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(true)).unwrap();
+        let err = execute(deps.as_mut(), later.clone(), mock_info("max", &[]), ExecuteMsg::ClaimRefund { id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "withdrawals"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(false)).unwrap();
+        let res = execute(deps.as_mut(), later.clone(), mock_info("max", &[]), ExecuteMsg::ClaimRefund { id: 2 }).unwrap();
+        assert_eq!(res.events[0].ty, "campaign_refund");
+        assert_eq!(query_pot(deps.as_ref(), "max", None).unwrap().collected, "40");
+        let err = execute(deps.as_mut(), later.clone(), mock_info("max", &[]), ExecuteMsg::ClaimRefund { id: 2 }).unwrap_err();
+        assert!(matches!(err, ContractError::NoContribution {}));
+        assert_eq!(query_contribution(deps.as_ref(), 2, "jane".to_string()).unwrap().amount, Uint128::new(20));
+        assert_eq!(query_campaign(deps.as_ref(), later, 2).unwrap().refunded, Uint128::new(40));
+        // the odd unit of campaign 1 is surplus, everything else is still owed
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(160));
+    }
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "alice".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
+    #[test]
+    fn test_split_templates() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec!["usei".to_string()] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let save = |name: &str, weights: Vec<u64>, immutable| ExecuteMsg::SaveSplitTemplate {
+            name: name.to_string(),
+            recipients: vec!["alice".to_string(), "bob".to_string(), "carol".to_string()],
+            weights,
+            immutable,
+        };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("gig", vec![1, 0, 1], false)).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTemplate {}));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("gig", vec![u64::MAX, 1, 1], false))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidTemplate {}));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("gig", vec![1, 1, 1], false)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("gig", vec![2, 1, 1], false)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("tour", vec![1, 1, 2], true)).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("rehearsal", vec![1, 1, 1], false)).unwrap();
+
+        // templates are found by name among the depositor's own, or by ID
+        let by_name = ExecuteMsg::CreatePot {
+            target_addr_1: None,
+            target_addr_2: None,
+            template: Some(TemplateRef::Name("gig".to_string())),
+            on_reject: RejectFallback::Fail,
+            mint_claims: false,
+            receive_msg: None,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("band", &coins(101, "uatom")), by_name.clone()).unwrap();
+        assert_eq!(res.events.len(), 3);
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), by_name).unwrap_err();
+        // a cw20 Send to this contract can reference a template as well
+        let by_id = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: String::from("max"),
+            amount: Uint128::new(100),
+            msg: to_binary(&ReceiveMsg::CreatePot {
+                target_addr_1: None,
+                target_addr_2: None,
+                template: Some(TemplateRef::Id(2)),
+                on_reject: RejectFallback::Fail,
+                mint_claims: false,
+            })
+            .unwrap(),
+        });
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("usei", &[]), by_id).unwrap();
+        let pots = query_pots(deps.as_ref(), vec!["alice".to_string(), "carol".to_string()], None).unwrap().pots;
+        assert_eq!((pots[0].collected.as_str(), pots[1].collected.as_str()), ("50", "25"));
+        let pot = query_pot(deps.as_ref(), "carol", Some("usei".to_string())).unwrap();
+        assert_eq!(pot.collected, "50");
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(100));
+        let mixed = ExecuteMsg::CreatePot {
+            target_addr_1: Some("alice".to_string()),
+            target_addr_2: Some("bob".to_string()),
+            template: Some(TemplateRef::Id(1)),
+            on_reject: RejectFallback::Fail,
+            mint_claims: false,
+            receive_msg: None,
+        };
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("band", &coins(100, "uatom")), mixed).unwrap_err();
+
+        // immutable templates stay as they are
+        let err = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), save("tour", vec![1, 1, 1], false)).unwrap_err();
+        assert!(matches!(err, ContractError::TemplateImmutable { name } if name == "tour"));
+        let msg = ExecuteMsg::DeleteSplitTemplate { name: "tour".to_string() };
+        let _err = execute(deps.as_mut(), env.clone(), mock_info("band", &[]), msg).unwrap_err();
+        let msg = ExecuteMsg::DeleteSplitTemplate { name: "rehearsal".to_string() };
+        let _res = execute(deps.as_mut(), env, mock_info("band", &[]), msg).unwrap();
+
+        let templates = query_split_templates(deps.as_ref(), "band".to_string(), None, None).unwrap().templates;
         assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("alice"),
-                collected: Uint128::new(25)
-            }
+            templates.iter().map(|t| (t.id, t.name.as_str(), t.weights.clone(), t.immutable)).collect::<Vec<_>>(),
+            vec![(1, "gig", vec![2, 1, 1], false), (2, "tour", vec![1, 1, 2], true)]
         );
+        let templates = query_split_templates(deps.as_ref(), "band".to_string(), Some("gig".to_string()), None).unwrap().templates;
+        assert_eq!(templates.len(), 1);
+    }
 
-        // Withdraw pot
-        let msg = ExecuteMsg::WithdrawPot { amount: Uint128::new(45) };
-        let info = mock_info("bob", &[]);
-        // info.sender = Addr::unchecked("bob");
-        let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    #[test]
+    fn test_forwarding() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "bob".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let deposit = split_deposit("alice", "bob", None);
+        let forward = |to: &str| ExecuteMsg::SetForward { to: to.to_string() };
+        let confirm = |from: &str| ExecuteMsg::ConfirmForward { from: from.to_string() };
+
+        // forwarding only starts once the new address confirms it
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), forward("wallet")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), deposit.clone()).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("carol", &[]), confirm("alice")).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("wallet", &[]), confirm("alice")).unwrap();
+        let res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), deposit).unwrap();
+        assert_eq!(res.events[0].attributes[2], Attribute::new("recipient", "wallet"));
+        let pots = query_pots(deps.as_ref(), vec!["alice".to_string(), "wallet".to_string()], None).unwrap().pots;
+        assert_eq!((pots[0].collected.as_str(), pots[1].collected.as_str()), ("50", "50"));
+
+        // chains are followed, loops refused
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("wallet", &[]), forward("vault")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("vault", &[]), confirm("wallet")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("vault", &[]), forward("alice")).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), confirm("vault")).unwrap_err();
+        assert!(matches!(err, ContractError::ForwardLoop {}));
+        let response = query_forward(deps.as_ref(), "alice".to_string()).unwrap();
         assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("bob"),
-                collected: Uint128::new(5)
-            }
+            response,
+            ForwardResponse { forward: Some("wallet".to_string()), pending: None, resolved: "vault".to_string() }
         );
+
+        // the old balance moves on request
+        let msg = ExecuteMsg::MoveBalance { asset: None };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoForward {}));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "pot_transfer");
+        let pots = query_pots(deps.as_ref(), vec!["alice".to_string(), "vault".to_string()], None).unwrap().pots;
+        assert_eq!((pots[0].collected.as_str(), pots[1].collected.as_str()), ("0", "50"));
+        let statement = query_statement(deps.as_ref(), "alice".to_string(), None, None).unwrap();
+        assert_eq!(statement.entries.last().unwrap().kind, LedgerKind::TransferOut);
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(200));
+
+        // a loop through the middle of the chain is refused too: anna -> cora, ben -> anna,
+        // then anna -> ben would leave anna and ben forwarding to each other
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anna", &[]), forward("cora")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("cora", &[]), confirm("anna")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("ben", &[]), forward("anna")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anna", &[]), confirm("ben")).unwrap();
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("anna", &[]), forward("ben")).unwrap();
+        let err = execute(deps.as_mut(), env, mock_info("ben", &[]), confirm("anna")).unwrap_err();
+        assert!(matches!(err, ContractError::ForwardLoop {}));
+        assert_eq!(query_forward(deps.as_ref(), "ben".to_string()).unwrap().resolved, "cora");
     }
 
     #[test]
-    fn test_create_pot() {
+    fn test_accept_policy() {
         let mut deps = mock_dependencies();
         let env = mock_env();
-        let mut info = mock_info("creator", &[]);
 
-        let msg = InstantiateMsg { owner: None };
-        let _res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let deposit = |on_reject| ExecuteMsg::CreatePot {
+            target_addr_1: Some(String::from("alice")),
+            target_addr_2: Some(String::from("bob")),
+            template: None,
+            on_reject,
+            mint_claims: false,
+            receive_msg: None,
+        };
+        let policy = AcceptPolicy::Allowlist { depositors: vec!["max".to_string()] };
+        let msg = ExecuteMsg::SetAcceptPolicy { policy: policy.clone() };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), msg).unwrap();
+        assert_eq!(query_accept_policy(deps.as_ref(), "bob".to_string()).unwrap(), policy);
+        assert_eq!(query_accept_policy(deps.as_ref(), "alice".to_string()).unwrap(), AcceptPolicy::AcceptAll {});
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
+        // allowlisted depositors go through, others fail by default
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), deposit(RejectFallback::Fail)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("carol", &coins(100, "uatom")), deposit(RejectFallback::Fail))
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Rejected { recipient } if recipient == "bob"));
+
+        // or get the refused share back in their own pot
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("carol", &coins(100, "uatom")),
+            deposit(RejectFallback::ReturnToDepositor),
+        )
+        .unwrap();
+        assert_eq!(res.events[1].ty, "pot_rejected");
+        let pots = query_pots(deps.as_ref(), vec!["alice".to_string(), "bob".to_string(), "carol".to_string()], None)
+            .unwrap()
+            .pots;
+        let collected: Vec<&str> = pots.iter().map(|p| p.collected.as_str()).collect();
+        assert_eq!(collected, vec!["100", "50", "50"]);
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(200));
+    }
+
+    #[test]
+    fn test_transfer_balance() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let msg = split_deposit("alice", "bob", None);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(200, "uatom")), msg).unwrap();
+
+        let transfer = |to: &str, amount| ExecuteMsg::TransferBalance { to: to.to_string(), amount: Uint128::new(amount), asset: None };
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), transfer("bob", 101)).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientBalance { balance } if balance == "100"));
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), transfer("bob", 0)).unwrap_err();
+        assert!(matches!(err, ContractError::ZeroAmount {}));
+        let res = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), transfer("bob", 30)).unwrap();
+        assert_eq!(res.messages.len(), 0);
+
+        // transfers are counted apart from withdrawals
+        let account = query_account(deps.as_ref(), "alice".to_string(), None).unwrap();
+        assert_eq!((account.withdrawn, account.transferred), (Uint128::zero(), Uint128::new(30)));
+
+        // accept policies apply to transfers as well
+        let msg = ExecuteMsg::SetAcceptPolicy { policy: AcceptPolicy::RejectAll {} };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("exchange", &[]), msg).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), transfer("exchange", 10)).unwrap_err();
+        assert!(matches!(err, ContractError::Rejected { recipient } if recipient == "exchange"));
+        assert_eq!(res.events[0].ty, "pot_transfer");
+
+        // Send credits the contract and calls its hook
+        let msg = ExecuteMsg::Send {
+            contract: "market".to_string(),
+            amount: Uint128::new(40),
+            asset: None,
+            msg: to_binary("buy").unwrap(),
+        };
+        let res = execute(deps.as_mut(), env, mock_info("bob", &[]), msg).unwrap();
+        let hook = PotReceiveMsg {
+            sender: "bob".to_string(),
+            asset: "uatom".to_string(),
+            amount: Uint128::new(40),
+            msg: to_binary("buy").unwrap(),
+        };
+        assert_eq!(res.messages, vec![SubMsg::new(hook.into_cosmos_msg("market").unwrap())]);
+
+        let pots = query_pots(deps.as_ref(), vec!["alice".to_string(), "bob".to_string(), "market".to_string()], None)
+            .unwrap()
+            .pots;
+        let collected: Vec<&str> = pots.iter().map(|p| p.collected.as_str()).collect();
+        assert_eq!(collected, vec!["70", "90", "40"]);
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(200));
+    }
+
+    #[test]
+    fn test_cw20_queries() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["usei".to_string(), "uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        for (denom, targets) in [("usei", ("alice", "bob")), ("uatom", ("carol", "dave"))] {
+            let msg = split_deposit(targets.0, targets.1, None);
+            let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, denom)), msg).unwrap();
+        }
+        // locked escrow balances are owed but not part of the supply
+        let msg = ExecuteMsg::CreateEscrowPot {
             target_addr_1: String::from("alice"),
             target_addr_2: String::from("bob"),
-            receive_msg: Cw20ReceiveMsg {
-                sender: String::from("cw20"),
-                amount: Uint128::new(100),
-                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
-            }
+            arbiter: String::from("judge"),
+            timeout: None,
+            milestones: vec![],
+            receive_msg: None,
         };
-        info.sender = Addr::unchecked("usei");
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(40, "usei")), msg).unwrap();
+        assert_eq!(LIABILITIES.load(&deps.storage, "usei").unwrap(), Uint128::new(140));
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
-            target_addr_1: String::from("max"),
-            target_addr_2: String::from("jane"),
-            receive_msg: Cw20ReceiveMsg {
-                sender: String::from("cw20"),
-                amount: Uint128::new(100),
-                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
-            }
+        // the primary asset is shown until a view is set
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Balance { address: "alice".to_string() }).unwrap();
+        assert_eq!(from_binary::<BalanceResponse>(&res).unwrap().balance, Uint128::new(50));
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::TokenInfo {}).unwrap();
+        let info: cw20::TokenInfoResponse = from_binary(&res).unwrap();
+        assert_eq!((info.symbol.as_str(), info.decimals, info.total_supply), ("usei", 6, Uint128::new(100)));
+        let msg = QueryMsg::AllAccounts { start_after: None, limit: None };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<AllAccountsResponse>(&res).unwrap().accounts, vec!["alice", "bob"]);
+
+        let msg = ExecuteMsg::SetTokenView {
+            asset: Some("uatom".to_string()),
+            name: "Atom claims".to_string(),
+            symbol: "CATOM".to_string(),
+            decimals: 6,
         };
-        info.sender = Addr::unchecked("usei");
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Balance { address: "alice".to_string() }).unwrap();
+        assert_eq!(from_binary::<BalanceResponse>(&res).unwrap().balance, Uint128::zero());
+        let msg = QueryMsg::AllAccounts { start_after: Some("carol".to_string()), limit: None };
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        assert_eq!(from_binary::<AllAccountsResponse>(&res).unwrap().accounts, vec!["dave"]);
+    }
 
-        // should create pot
-        let msg = ExecuteMsg::CreatePot {
-            target_addr_1: String::from("karren"),
-            target_addr_2: String::from("john"),
-            receive_msg: Cw20ReceiveMsg {
-                sender: String::from("cw20"),
-                amount: Uint128::new(100),
-                msg: to_binary(&ReceiveMsg::Send { id: Uint64::new(1) }).unwrap(),
-            }
+    #[test]
+    fn test_claim_tokens() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg { owner: None, native_denoms: vec!["uatom".to_string()], cw20_tokens: vec![] };
+        let _res = instantiate(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        let deposit = ExecuteMsg::CreatePot {
+            target_addr_1: Some(String::from("alice")),
+            target_addr_2: Some(String::from("bob")),
+            template: None,
+            on_reject: RejectFallback::Fail,
+            mint_claims: true,
+            receive_msg: None,
         };
-        info.sender = Addr::unchecked("usei");
-        let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
-        assert_eq!(res.messages.len(), 0);
+        let err = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), deposit.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NoClaimToken { .. }));
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "alice".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
-        assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("alice"),
-                collected: Uint128::new(50)
-            }
-        );
+        // the claim token is recorded once its instantiation replies
+        let msg = ExecuteMsg::CreateClaimToken {
+            asset: None,
+            code_id: 7,
+            name: "Atom claims".to_string(),
+            symbol: "CATOM".to_string(),
+            decimals: 6,
+        };
+        let res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Success);
+        let reply_msg = Reply {
+            id: CLAIM_TOKEN_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![Event::new("instantiate").add_attribute("_contract_address", "claims")],
+                data: None,
+            }),
+        };
+        let _res = reply(deps.as_mut(), env.clone(), reply_msg).unwrap();
+        let token = query_claim_token(deps.as_ref(), None).unwrap();
+        assert_eq!(token, ClaimTokenResponse { asset: "uatom".to_string(), token: Some("claims".to_string()) });
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "bob".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
-        assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("bob"),
-                collected: Uint128::new(50)
-            }
-        );
+        // shares are minted instead of credited; zero shares are not minted at all
+        let res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(1, "uatom")), deposit.clone()).unwrap();
+        assert!(res.messages.is_empty());
+        let res = execute(deps.as_mut(), env.clone(), mock_info("max", &coins(100, "uatom")), deposit).unwrap();
+        let mint = Cw20Contract(Addr::unchecked("claims"))
+            .call(Cw20ExecuteMsg::Mint { recipient: "alice".to_string(), amount: Uint128::new(50) })
+            .unwrap();
+        assert_eq!(res.messages[0], SubMsg::new(mint));
+        assert_eq!(res.events[0].ty, "claim_minted");
+        assert_eq!(query_pot(deps.as_ref(), "alice", None).unwrap().collected, "0");
+        assert_eq!(LIABILITIES.load(&deps.storage, "uatom").unwrap(), Uint128::new(100));
 
-        // query pot
-        let msg = QueryMsg::GetPot { addr: "karren".to_string() };
-        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
-        let pot: Pot = from_binary(&res).unwrap();
-        assert_eq!(
-            pot,
-            Pot {
-                target_addr: Addr::unchecked("karren"),
-                collected: Uint128::new(50)
-            }
-        );
+        // whoever holds the tokens redeems them into their pot
+        let redeem = |amount| ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "carol".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&ReceiveMsg::Redeem {}).unwrap(),
+        });
+        let err = execute(deps.as_mut(), env.clone(), mock_info("fake", &[]), redeem(30)).unwrap_err();
+        assert!(matches!(err, ContractError::UnacceptedAsset { .. }));
+        let pause = |deposits| ExecuteMsg::SetPause { deposits, withdrawals: false };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(true)).unwrap();
+        let err = execute(deps.as_mut(), env.clone(), mock_info("claims", &[]), redeem(30)).unwrap_err();
+        assert!(matches!(err, ContractError::Paused { scope } if scope == "deposits"));
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), pause(false)).unwrap();
+        let res = execute(deps.as_mut(), env, mock_info("claims", &[]), redeem(30)).unwrap();
+        let burn = Cw20Contract(Addr::unchecked("claims")).call(Cw20ExecuteMsg::Burn { amount: Uint128::new(30) }).unwrap();
+        assert_eq!(res.messages, vec![SubMsg::new(burn)]);
+        assert_eq!(query_pot(deps.as_ref(), "carol", None).unwrap().collected, "30");
+        let statement = query_statement(deps.as_ref(), "carol".to_string(), None, None).unwrap();
+        assert_eq!(statement.entries[0].kind, LedgerKind::Redemption);
     }
 
     #[test]
@@ -326,7 +4311,11 @@ mod tests {
         let info = mock_info("creator", &[]);
 
         //no owner specified in the instantiation message
-        let msg = InstantiateMsg { owner: None };
+        let msg = InstantiateMsg {
+            owner: None,
+            native_denoms: vec![],
+            cw20_tokens: vec!["usei".to_string()],
+        };
         let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
         assert_eq!(0, res.messages.len());
 
@@ -336,12 +4325,17 @@ mod tests {
             state,
             Config {
                 owner: Addr::unchecked("creator".to_string()),
+                native_denoms: vec![],
+                cw20_tokens: vec![Addr::unchecked("usei")],
+                primary_asset: Denom::Cw20(Addr::unchecked("usei")),
             }
         );
 
         //specifying an owner address in the instantiation message
         let msg = InstantiateMsg {
             owner: Some("specified_owner".to_string()),
+            native_denoms: vec!["usei".to_string()],
+            cw20_tokens: vec![],
         };
 
         let res = instantiate(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
@@ -353,6 +4347,9 @@ mod tests {
             state,
             Config {
                 owner: Addr::unchecked("specified_owner".to_string()),
+                native_denoms: vec!["usei".to_string()],
+                cw20_tokens: vec![],
+                primary_asset: Denom::Native("usei".to_string()),
             }
         );
 
@@ -371,6 +4368,58 @@ mod tests {
             "not_owner"
         );
 
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::ContractInfo {}).unwrap();
+        let info: ContractInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            info,
+            ContractInfoResponse {
+                contract: CONTRACT_NAME.to_string(),
+                version: CONTRACT_VERSION.to_string(),
+                features: FEATURES.iter().map(|feature| feature.to_string()).collect(),
+                instantiated_height: env.block.height,
+                instantiated_time: env.block.time,
+            }
+        );
+        assert!(info.features.contains(&"escrow".to_string()));
+        assert!(info.features.contains(&"claim_tokens".to_string()));
+
+        let pause = ExecuteMsg::SetPause { deposits: true, withdrawals: false };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("specified_owner", &[]), pause).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: ConfigResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            config,
+            ConfigResponse {
+                owner: "specified_owner".to_string(),
+                native_denoms: vec!["usei".to_string()],
+                cw20_tokens: vec![],
+                primary_asset: "usei".to_string(),
+                pause: PauseState {
+                    deposits: true,
+                    withdrawals: false,
+                    set_by: Some(Addr::unchecked("specified_owner")),
+                },
+                default_limit: DEFAULT_LIMIT,
+                max_limit: MAX_LIMIT,
+                proposal_ttl: DEFAULT_PROPOSAL_TTL,
+            }
+        );
+
+        // both queries follow an ownership handover
+        let grant = ExecuteMsg::GrantRole { role: Role::Owner, addr: String::from("successor") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("specified_owner", &[]), grant).unwrap();
+        let revoke = ExecuteMsg::RevokeRole { role: Role::Owner, addr: String::from("specified_owner") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("successor", &[]), revoke).unwrap();
+        // creator is still an owner from the first instantiation
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::QueryOwner {}).unwrap();
+        assert_eq!(from_binary::<Config>(&res).unwrap().owner, "creator");
+        let revoke = ExecuteMsg::RevokeRole { role: Role::Owner, addr: String::from("creator") };
+        let _res = execute(deps.as_mut(), env.clone(), mock_info("successor", &[]), revoke).unwrap();
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        assert_eq!(from_binary::<ConfigResponse>(&res).unwrap().owner, "successor");
+        let res = query(deps.as_ref(), env, QueryMsg::QueryOwner {}).unwrap();
+        assert_eq!(from_binary::<Config>(&res).unwrap().owner, "successor");
+
     }
 
     /*
@@ -11,6 +11,90 @@ pub enum ContractError {
 
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
+
+    #[error("Cannot set allowance to own account")]
+    CannotSetOwnAccount {},
+
+    #[error("Invalid expiration value")]
+    InvalidExpiration {},
+
+    #[error("Allowance is expired")]
+    Expired {},
+
+    #[error("No allowance for this account")]
+    NoAllowance {},
+
+    #[error("Contract is paused for {scope}")]
+    Paused { scope: String },
+
+    #[error("Cannot revoke the last owner")]
+    LastOwner {},
+
+    #[error("Amount must be positive")]
+    ZeroAmount {},
+
+    #[error("Insufficient balance: {balance} available")]
+    InsufficientBalance { balance: String },
+
+    #[error("No surplus to sweep")]
+    NoSurplus {},
+
+    #[error("At least one native denom or cw20 token must be accepted")]
+    NoAcceptedAsset {},
+
+    #[error("Asset {asset} is not accepted")]
+    UnacceptedAsset { asset: String },
+
+    #[error("Asset {asset} is the primary asset and cannot be removed")]
+    PrimaryAsset { asset: String },
+
+    #[error("Escrow {id} is already closed")]
+    EscrowClosed { id: u64 },
+
+    #[error("Milestone shares must be positive and add up to 1")]
+    InvalidMilestones {},
+
+    #[error("Milestone {expected} must be completed first")]
+    MilestoneOutOfOrder { expected: usize },
+
+    #[error("Proposal {id} has expired")]
+    ProposalExpired { id: u64 },
+
+    #[error("Campaign {id} does not take contributions anymore")]
+    CampaignClosed { id: u64 },
+
+    #[error("Campaign {id} has not reached its goal")]
+    GoalNotReached { id: u64 },
+
+    #[error("Campaign {id} has not failed")]
+    CampaignNotFailed { id: u64 },
+
+    #[error("No contribution to refund")]
+    NoContribution {},
+
+    #[error("Template needs as many positive weights as recipients")]
+    InvalidTemplate {},
+
+    #[error("Template {name} is immutable")]
+    TemplateImmutable { name: String },
+
+    #[error("Forwarding would create a loop")]
+    ForwardLoop {},
+
+    #[error("No forwarding address")]
+    NoForward {},
+
+    #[error("{recipient} does not accept this deposit")]
+    Rejected { recipient: String },
+
+    #[error("Asset {asset} already has a claim token")]
+    ClaimTokenExists { asset: String },
+
+    #[error("Asset {asset} has no claim token")]
+    NoClaimToken { asset: String },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -0,0 +1,369 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use cosmwasm_std::{Event, Uint128};
+
+// Every balance change is reported with one of the events below. Their types and attribute
+// names are stable so an indexer can rebuild all balances from events alone. The structs are
+// exported to the schema directory; each field is emitted as an attribute of the same name.
+
+/// PotDepositEvent is emitted as `pot_deposit` for every share credited by CreatePot or by the
+/// release of an escrow.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotDepositEvent {
+    /// deposit_id is the ID of the entry in the deposit log.
+    pub deposit_id: u64,
+    pub depositor: String,
+    pub recipient: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the recipient's pot balance after the deposit.
+    pub balance: Uint128,
+}
+
+impl From<PotDepositEvent> for Event {
+    fn from(event: PotDepositEvent) -> Self {
+        Event::new("pot_deposit")
+            .add_attribute("deposit_id", event.deposit_id.to_string())
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("recipient", event.recipient)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// PotWithdrawEvent is emitted as `pot_withdraw` for every amount paid out of a pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotWithdrawEvent {
+    /// owner is the address whose pot was debited.
+    pub owner: String,
+    /// recipient is the address the tokens were sent to.
+    pub recipient: String,
+    /// spender is set when the withdrawal used an allowance; the attribute is left out otherwise.
+    pub spender: Option<String>,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the owner's pot balance after the withdrawal.
+    pub balance: Uint128,
+}
+
+impl From<PotWithdrawEvent> for Event {
+    fn from(event: PotWithdrawEvent) -> Self {
+        let mut res = Event::new("pot_withdraw")
+            .add_attribute("owner", event.owner)
+            .add_attribute("recipient", event.recipient);
+        if let Some(spender) = event.spender {
+            res = res.add_attribute("spender", spender);
+        }
+        res.add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// EscrowCreatedEvent is emitted as `escrow_created` when CreateEscrowPot locks a deposit.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowCreatedEvent {
+    pub escrow_id: u64,
+    pub depositor: String,
+    pub arbiter: String,
+    pub recipient_1: String,
+    pub recipient_2: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// locked is the amount locked for each recipient.
+    pub locked: Uint128,
+}
+
+impl From<EscrowCreatedEvent> for Event {
+    fn from(event: EscrowCreatedEvent) -> Self {
+        Event::new("escrow_created")
+            .add_attribute("escrow_id", event.escrow_id.to_string())
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("arbiter", event.arbiter)
+            .add_attribute("recipient_1", event.recipient_1)
+            .add_attribute("recipient_2", event.recipient_2)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("locked", event.locked)
+    }
+}
+
+/// PotRefundEvent is emitted as `pot_refund` when an escrow is refunded to the depositor's pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotRefundEvent {
+    pub escrow_id: u64,
+    pub depositor: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the depositor's pot balance after the refund.
+    pub balance: Uint128,
+}
+
+impl From<PotRefundEvent> for Event {
+    fn from(event: PotRefundEvent) -> Self {
+        Event::new("pot_refund")
+            .add_attribute("escrow_id", event.escrow_id.to_string())
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// CampaignContributionEvent is emitted as `campaign_contribution` for every contribution to a
+/// campaign.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignContributionEvent {
+    pub campaign_id: u64,
+    pub contributor: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// contribution is the contributor's total to the campaign after this one.
+    pub contribution: Uint128,
+    /// raised is the campaign total after this contribution.
+    pub raised: Uint128,
+}
+
+impl From<CampaignContributionEvent> for Event {
+    fn from(event: CampaignContributionEvent) -> Self {
+        Event::new("campaign_contribution")
+            .add_attribute("campaign_id", event.campaign_id.to_string())
+            .add_attribute("contributor", event.contributor)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("contribution", event.contribution)
+            .add_attribute("raised", event.raised)
+    }
+}
+
+/// CampaignRefundEvent is emitted as `campaign_refund` when a contribution to a failed campaign is
+/// credited back to the contributor's pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignRefundEvent {
+    pub campaign_id: u64,
+    pub contributor: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the contributor's pot balance after the refund.
+    pub balance: Uint128,
+}
+
+impl From<CampaignRefundEvent> for Event {
+    fn from(event: CampaignRefundEvent) -> Self {
+        Event::new("campaign_refund")
+            .add_attribute("campaign_id", event.campaign_id.to_string())
+            .add_attribute("contributor", event.contributor)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// JointPotDepositEvent is emitted as `joint_pot_deposit` when CreateJointPot credits a joint pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JointPotDepositEvent {
+    pub depositor: String,
+    pub member_1: String,
+    pub member_2: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the joint pot balance after the deposit.
+    pub balance: Uint128,
+}
+
+impl From<JointPotDepositEvent> for Event {
+    fn from(event: JointPotDepositEvent) -> Self {
+        Event::new("joint_pot_deposit")
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("member_1", event.member_1)
+            .add_attribute("member_2", event.member_2)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// JointPotWithdrawEvent is emitted as `joint_pot_withdraw` when a joint withdrawal is confirmed.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JointPotWithdrawEvent {
+    pub proposal_id: u64,
+    pub member_1: String,
+    pub member_2: String,
+    pub recipient: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the joint pot balance after the withdrawal.
+    pub balance: Uint128,
+}
+
+impl From<JointPotWithdrawEvent> for Event {
+    fn from(event: JointPotWithdrawEvent) -> Self {
+        Event::new("joint_pot_withdraw")
+            .add_attribute("proposal_id", event.proposal_id.to_string())
+            .add_attribute("member_1", event.member_1)
+            .add_attribute("member_2", event.member_2)
+            .add_attribute("recipient", event.recipient)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// PotRejectedEvent is emitted as `pot_rejected` when a CreatePot share refused by its recipient
+/// is credited back to the depositor's pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotRejectedEvent {
+    pub depositor: String,
+    /// recipient is the address that refused the share.
+    pub recipient: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the depositor's pot balance after the share was credited back.
+    pub balance: Uint128,
+}
+
+impl From<PotRejectedEvent> for Event {
+    fn from(event: PotRejectedEvent) -> Self {
+        Event::new("pot_rejected")
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("recipient", event.recipient)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// ClaimMintedEvent is emitted as `claim_minted` for every CreatePot share paid in claim tokens
+/// instead of being credited to a pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimMintedEvent {
+    /// deposit_id is the ID of the entry in the deposit log.
+    pub deposit_id: u64,
+    pub depositor: String,
+    pub recipient: String,
+    pub asset: String,
+    /// token is the claim token contract.
+    pub token: String,
+    pub amount: Uint128,
+}
+
+impl From<ClaimMintedEvent> for Event {
+    fn from(event: ClaimMintedEvent) -> Self {
+        Event::new("claim_minted")
+            .add_attribute("deposit_id", event.deposit_id.to_string())
+            .add_attribute("depositor", event.depositor)
+            .add_attribute("recipient", event.recipient)
+            .add_attribute("asset", event.asset)
+            .add_attribute("token", event.token)
+            .add_attribute("amount", event.amount)
+    }
+}
+
+/// ClaimRedeemedEvent is emitted as `claim_redeemed` when claim tokens are burnt and their amount
+/// credited to the holder's pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimRedeemedEvent {
+    pub holder: String,
+    pub asset: String,
+    pub token: String,
+    pub amount: Uint128,
+    /// balance is the holder's pot balance after the redemption.
+    pub balance: Uint128,
+}
+
+impl From<ClaimRedeemedEvent> for Event {
+    fn from(event: ClaimRedeemedEvent) -> Self {
+        Event::new("claim_redeemed")
+            .add_attribute("holder", event.holder)
+            .add_attribute("asset", event.asset)
+            .add_attribute("token", event.token)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+    }
+}
+
+/// PotTransferEvent is emitted as `pot_transfer` when a balance moves from one pot to another
+/// without leaving the contract.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotTransferEvent {
+    pub from: String,
+    pub to: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// from_balance is the sender's pot balance after the transfer.
+    pub from_balance: Uint128,
+    /// to_balance is the receiver's pot balance after the transfer.
+    pub to_balance: Uint128,
+}
+
+impl From<PotTransferEvent> for Event {
+    fn from(event: PotTransferEvent) -> Self {
+        Event::new("pot_transfer")
+            .add_attribute("from", event.from)
+            .add_attribute("to", event.to)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("from_balance", event.from_balance)
+            .add_attribute("to_balance", event.to_balance)
+    }
+}
+
+/// PotWithdrawFailedEvent is emitted as `pot_withdraw_failed` when a contract recipient rejects
+/// a withdrawal; the amount is credited back to the owner's pot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotWithdrawFailedEvent {
+    pub owner: String,
+    pub recipient: String,
+    pub asset: String,
+    pub amount: Uint128,
+    /// balance is the owner's pot balance after the amount was credited back.
+    pub balance: Uint128,
+    /// error is the error returned by the transfer.
+    pub error: String,
+}
+
+impl From<PotWithdrawFailedEvent> for Event {
+    fn from(event: PotWithdrawFailedEvent) -> Self {
+        Event::new("pot_withdraw_failed")
+            .add_attribute("owner", event.owner)
+            .add_attribute("recipient", event.recipient)
+            .add_attribute("asset", event.asset)
+            .add_attribute("amount", event.amount)
+            .add_attribute("balance", event.balance)
+            .add_attribute("error", event.error)
+    }
+}
+
+/// OwnerChange says whether an address gained or lost the owner role.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OwnerChange {
+    Added,
+    Removed,
+}
+
+impl OwnerChange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OwnerChange::Added => "added",
+            OwnerChange::Removed => "removed",
+        }
+    }
+}
+
+/// OwnerChangedEvent is emitted as `owner_changed` at instantiation and whenever the owner
+/// role is granted or revoked.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerChangedEvent {
+    pub owner: String,
+    pub change: OwnerChange,
+}
+
+impl From<OwnerChangedEvent> for Event {
+    fn from(event: OwnerChangedEvent) -> Self {
+        Event::new("owner_changed")
+            .add_attribute("owner", event.owner)
+            .add_attribute("change", event.change.as_str())
+    }
+}
@@ -1,27 +1,239 @@
 use schemars::JsonSchema;
-use cw20::Cw20ReceiveMsg;
-use cosmwasm_std::{Uint128, Uint64};
+use cw20::{Cw20Coin, Cw20ReceiveMsg, Denom, Expiration, MinterResponse};
+use cosmwasm_std::{
+    to_binary, Binary, CosmosMsg, Decimal, StdResult, Timestamp, Uint128, Uint64, WasmMsg,
+};
 use serde::{Deserialize, Serialize};
 
+use crate::state::{
+    AcceptPolicy, CampaignStatus, EscrowStatus, LedgerKind, PauseState, RejectFallback, Role,
+};
+
+/// InstantiateMsg needs at least one accepted asset across native_denoms and cw20_tokens.
+/// Duplicates are dropped.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: Option<String>,
+    /// native_denoms lists the bank denoms CreatePot accepts.
+    #[serde(default)]
+    pub native_denoms: Vec<String>,
+    /// cw20_tokens lists the cw20 contracts CreatePot accepts.
+    #[serde(default)]
+    pub cw20_tokens: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Splits the deposit in half between target_addr_1 and target_addr_2, or by weight between
+    /// the recipients of template when given instead.
     CreatePot {
         /// target_addr will receive tokens when token amount threshold is met.
-        target_addr_1: String,
+        #[serde(default)]
+        target_addr_1: Option<String>,
         /// target_addr will receive tokens when token amount threshold is met.
+        #[serde(default)]
+        target_addr_2: Option<String>,
+        #[serde(default)]
+        template: Option<TemplateRef>,
+        /// on_reject says what happens to a share whose recipient's accept policy refuses it.
+        #[serde(default)]
+        on_reject: RejectFallback,
+        /// mint_claims pays the shares in the asset's claim token instead of crediting pots.
+        #[serde(default)]
+        mint_claims: bool,
+        /// receive_msg is set when an accepted cw20 contract calls CreatePot on behalf of the
+        /// depositor. Leave it out to deposit the native coin sent along with the message.
+        receive_msg: Option<Cw20ReceiveMsg>,
+    },
+    /// Deposits like CreatePot, but the shares stay locked until arbiter calls Release or Refund.
+    CreateEscrowPot {
+        target_addr_1: String,
+        target_addr_2: String,
+        arbiter: String,
+        /// timeout lets the depositor call Refund once it has expired.
+        timeout: Option<Expiration>,
+        /// milestones split the release into ordered tranches. Their shares must add up to 1.
+        #[serde(default)]
+        milestones: Vec<MilestoneMsg>,
+        receive_msg: Option<Cw20ReceiveMsg>,
+    },
+    /// Credits what is left in the escrow to the recipients' pots. Arbiter only.
+    Release { id: u64 },
+    /// Marks the next milestone of escrow id complete and credits its tranche. Arbiter only.
+    CompleteMilestone { id: u64, milestone: usize },
+    /// Credits the escrowed funds back to the depositor's pot.
+    /// Arbiter only, or the depositor once the timeout has expired.
+    Refund { id: u64 },
+    /// Deposits like CreatePot into a single pot shared by both targets. Withdrawing from it
+    /// takes a proposal from one target and a confirmation from the other.
+    CreateJointPot {
+        target_addr_1: String,
         target_addr_2: String,
-        receive_msg: Cw20ReceiveMsg,
+        receive_msg: Option<Cw20ReceiveMsg>,
     },
+    /// Proposes to pay amount from the joint pot shared with partner to recipient.
+    /// The proposal expires after the configured proposal TTL.
+    ProposeJointWithdrawal {
+        partner: String,
+        recipient: String,
+        amount: Uint128,
+        asset: Option<String>,
+    },
+    /// Confirms and executes proposal id. Only the partner named in the proposal can confirm.
+    ConfirmJointWithdrawal { id: u64 },
+    /// Opens a campaign collecting asset towards goal until deadline. Once the goal is reached,
+    /// Finalize splits the funds between the targets; otherwise contributors get refunds.
+    CreateCampaign {
+        target_addr_1: String,
+        target_addr_2: String,
+        asset: String,
+        goal: Uint128,
+        deadline: Expiration,
+    },
+    /// Contributes the coin sent along, or the wrapped cw20 amount, to campaign id.
+    Contribute {
+        id: u64,
+        receive_msg: Option<Cw20ReceiveMsg>,
+    },
+    /// Credits the funds of a campaign that reached its goal to the targets' pots. Anyone can call it.
+    Finalize { id: u64 },
+    /// Credits the sender's contribution to a failed campaign back to its pot.
+    ClaimRefund { id: u64 },
     WithdrawPot {
         // The amount you want to withdraw
         amount: Uint128,
-    }
+        /// asset defaults to the primary asset, fixed at instantiation to the first native
+        /// denom, or else the first cw20 token.
+        asset: Option<String>,
+    },
+    /// Allows spender to withdraw up to amount from the sender's pot.
+    /// If an allowance already exists, it is increased by amount.
+    IncreaseWithdrawAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        asset: Option<String>,
+    },
+    /// Lowers the spender's withdraw allowance by amount.
+    /// The allowance is removed if it reaches zero. Available while withdrawals are paused.
+    DecreaseWithdrawAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        asset: Option<String>,
+    },
+    /// Withdraws amount from owner's pot to recipient, using the sender's allowance.
+    WithdrawFrom {
+        owner: String,
+        recipient: String,
+        amount: Uint128,
+        asset: Option<String>,
+    },
+    /// Gives role to addr. Owner only.
+    GrantRole {
+        role: Role,
+        addr: String,
+    },
+    /// Takes role away from addr. Owner only.
+    RevokeRole {
+        role: Role,
+        addr: String,
+    },
+    /// Freezes or unfreezes deposits and withdrawals. Owner or pauser only.
+    SetPause {
+        deposits: bool,
+        withdrawals: bool,
+    },
+    /// Sends the part of the contract's asset balance that is not owed to anyone to recipient.
+    /// Owner only. Blocked while withdrawals are paused.
+    SweepSurplus {
+        asset: Denom,
+        recipient: String,
+    },
+    /// Sets which CreatePot deposits the sender accepts.
+    SetAcceptPolicy { policy: AcceptPolicy },
+    /// Asks for the sender's future credits to go to `to`. Takes effect once `to` confirms it.
+    SetForward { to: String },
+    /// Accepts the forwarding requested by `from` to the sender.
+    ConfirmForward { from: String },
+    /// Stops forwarding the sender's credits and drops any unconfirmed request.
+    RemoveForward {},
+    /// Moves the sender's whole balance of asset to the account its credits are forwarded to.
+    MoveBalance { asset: Option<String> },
+    /// Moves amount from the sender's pot to the pot of `to` without moving any tokens.
+    /// Fails if the accept policy of `to` refuses the sender.
+    TransferBalance {
+        to: String,
+        amount: Uint128,
+        asset: Option<String>,
+    },
+    /// Moves amount from the sender's pot to the pot of contract, then calls contract with
+    /// PotReceiveMsg, like cw20 Send.
+    Send {
+        contract: String,
+        amount: Uint128,
+        asset: Option<String>,
+        msg: Binary,
+    },
+    /// Saves a split template under name for the sender, replacing its previous version
+    /// unless that one is immutable.
+    SaveSplitTemplate {
+        name: String,
+        recipients: Vec<String>,
+        weights: Vec<u64>,
+        #[serde(default)]
+        immutable: bool,
+    },
+    /// Deletes the sender's template name. Immutable templates cannot be deleted.
+    DeleteSplitTemplate { name: String },
+    /// Sets how long joint withdrawal proposals stay open. Owner or operator only.
+    SetProposalTtl { seconds: u64 },
+    /// Instantiates code_id as the claim token of asset, with this contract as minter.
+    /// asset defaults to the primary asset. Owner or operator only.
+    CreateClaimToken {
+        asset: Option<String>,
+        code_id: u64,
+        name: String,
+        symbol: String,
+        decimals: u8,
+    },
+    /// cw20 receive hook. Accepted tokens sent with ReceiveMsg::CreatePot, CreateEscrowPot,
+    /// CreateJointPot or Contribute are deposited; claim tokens sent with ReceiveMsg::Redeem are
+    /// burnt and credited 1:1 to the sender's pot.
+    Receive(Cw20ReceiveMsg),
+    /// Sets the asset and metadata reported by the cw20 queries. asset defaults to the
+    /// primary asset. Owner or operator only.
+    SetTokenView {
+        asset: Option<String>,
+        name: String,
+        symbol: String,
+        decimals: u8,
+    },
+    /// Starts accepting asset in CreatePot. Owner or operator only.
+    AddAcceptedAsset {
+        asset: Denom,
+    },
+    /// Stops accepting asset in CreatePot. Existing balances can still be withdrawn.
+    /// The primary asset cannot be removed. Owner or operator only.
+    RemoveAcceptedAsset {
+        asset: Denom,
+    },
+}
+
+/// TemplateRef points at a split template by ID, or by name among the depositor's templates.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateRef {
+    Id(u64),
+    Name(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestoneMsg {
+    pub description: String,
+    /// share is the part of the deposit this milestone releases.
+    pub share: Decimal,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -29,20 +241,442 @@ pub enum ExecuteMsg {
 pub enum ReceiveMsg {
     // Send sends token to an id with defined pot
     Send { id: Uint64 },
+    /// CreatePot deposits the tokens received like ExecuteMsg::CreatePot. This is how a cw20
+    /// Send to this contract is split.
+    CreatePot {
+        #[serde(default)]
+        target_addr_1: Option<String>,
+        #[serde(default)]
+        target_addr_2: Option<String>,
+        #[serde(default)]
+        template: Option<TemplateRef>,
+        #[serde(default)]
+        on_reject: RejectFallback,
+        #[serde(default)]
+        mint_claims: bool,
+    },
+    /// CreateEscrowPot locks the tokens received in an escrow like ExecuteMsg::CreateEscrowPot.
+    CreateEscrowPot {
+        target_addr_1: String,
+        target_addr_2: String,
+        arbiter: String,
+        timeout: Option<Expiration>,
+        #[serde(default)]
+        milestones: Vec<MilestoneMsg>,
+    },
+    /// CreateJointPot deposits the tokens received like ExecuteMsg::CreateJointPot.
+    CreateJointPot {
+        target_addr_1: String,
+        target_addr_2: String,
+    },
+    /// Contribute adds the tokens received to campaign id like ExecuteMsg::Contribute.
+    Contribute { id: u64 },
+    /// Redeem burns the claim tokens sent and credits their asset to the sender's pot.
+    Redeem {},
+}
+
+/// ClaimTokenInstantiateMsg is the InstantiateMsg of cw20-base, used for claim tokens.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimTokenInstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<Cw20Coin>,
+    pub mint: Option<MinterResponse>,
+}
+
+/// PotReceiveMsg is sent to the contract named in Send once its pot has been credited.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotReceiveMsg {
+    pub sender: String,
+    pub asset: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+impl PotReceiveMsg {
+    /// into_cosmos_msg wraps the message as `{"pot_receive": {..}}` for contract_addr.
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_binary(&PotReceiverExecuteMsg::PotReceive(self))?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// PotReceiverExecuteMsg is the part of a receiving contract's ExecuteMsg that handles Send.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PotReceiverExecuteMsg {
+    PotReceive(PotReceiveMsg),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     QueryOwner {},
-    GetPot { addr: String },
+    /// Returns the owner, accepted assets, pause state and query limits.
+    /// Return type: ConfigResponse.
+    Config {},
+    /// Returns the cw2 contract name and version, the supported features and when the
+    /// contract was instantiated.
+    /// Return type: ContractInfoResponse.
+    ContractInfo {},
+    /// Returns the pot of addr. Addresses that were never credited come back with a zero balance.
+    /// asset defaults to the primary asset.
+    /// Return type: PotResponse.
+    GetPot { addr: String, asset: Option<String> },
+    /// Returns the pot of every address in addrs, in the same order.
+    /// Addresses that were never credited come back with a zero balance. At most 30 addresses.
+    /// Return type: PotsResponse.
+    GetPots { addrs: Vec<String>, asset: Option<String> },
+    /// Returns an escrow created by CreateEscrowPot.
+    /// Return type: EscrowResponse.
+    Escrow { id: u64 },
+    /// Lists the milestones of an escrow with what each releases per recipient.
+    /// Return type: MilestonesResponse.
+    Milestones { id: u64 },
+    /// Returns the accept policy of addr.
+    /// Return type: state::AcceptPolicy.
+    AcceptPolicy { addr: String },
+    /// Returns the forwarding of addr.
+    /// Return type: ForwardResponse.
+    Forward { addr: String },
+    /// Lists the split templates of owner by name. Supports pagination.
+    /// Return type: SplitTemplatesResponse.
+    ListSplitTemplates {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns a campaign and its progress towards the goal.
+    /// Return type: CampaignResponse.
+    Campaign { id: u64 },
+    /// Returns what contributor put into campaign id.
+    /// Return type: ContributionResponse.
+    Contribution { id: u64, contributor: String },
+    /// Returns the joint pot shared by addr_1 and addr_2, in either order.
+    /// Return type: JointPotResponse.
+    JointPot {
+        addr_1: String,
+        addr_2: String,
+        asset: Option<String>,
+    },
+    /// Returns a joint withdrawal proposal.
+    /// Return type: JointProposalResponse.
+    JointProposal { id: u64 },
+    /// Returns the balance of addr along with lifetime received and withdrawn totals.
+    /// Return type: AccountResponse.
+    GetAccount { addr: String, asset: Option<String> },
+    /// Returns how much spender can withdraw from owner's pot, 0 if unset.
+    /// Return type: cw20::AllowanceResponse.
+    WithdrawAllowance {
+        owner: String,
+        spender: String,
+        asset: Option<String>,
+    },
+    /// Returns the current pause scopes and who set them.
+    /// Return type: state::PauseState.
+    PauseState {},
+    /// Returns the roles held by addr.
+    /// Return type: RolesResponse.
+    Roles { addr: String },
+    /// Lists the addresses holding role. Supports pagination.
+    /// Return type: RoleMembersResponse.
+    RoleMembers {
+        role: Role,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Lists the deposits sent by sender, oldest first. Supports pagination.
+    /// Return type: DepositsResponse.
+    DepositsBySender {
+        sender: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Lists the deposits credited to recipient, oldest first. Supports pagination.
+    /// Return type: DepositsResponse.
+    DepositsByRecipient {
+        recipient: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Lists every credit and debit of addr with the running balance, oldest first.
+    /// Supports pagination.
+    /// Return type: StatementResponse.
+    Statement {
+        addr: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Compares what the contract owes with what it actually holds, per asset.
+    /// Return type: SolvencyResponse.
+    Solvency {},
+    /// Returns lifetime totals per asset.
+    /// Return type: StatsResponse.
+    Stats {},
+    /// Returns the claim token of asset, which defaults to the primary asset.
+    /// Return type: ClaimTokenResponse.
+    ClaimToken { asset: Option<String> },
+    /// cw20 compatible: returns the pot balance of address in the token view asset.
+    /// Return type: cw20::BalanceResponse.
+    Balance { address: String },
+    /// cw20 compatible: returns the token view metadata. total_supply is the sum of all pot
+    /// balances in the asset, so it matches the balances listed by AllAccounts.
+    /// Return type: cw20::TokenInfoResponse.
+    TokenInfo {},
+    /// cw20 compatible: lists the addresses with a pot in the token view asset.
+    /// Return type: cw20::AllAccountsResponse.
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 // We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub owner: String,
+    /// native_denoms lists the bank denoms CreatePot accepts.
+    pub native_denoms: Vec<String>,
+    /// cw20_tokens lists the cw20 contracts CreatePot accepts.
+    pub cw20_tokens: Vec<String>,
+    /// primary_asset is the asset messages default to when they leave it out.
+    pub primary_asset: String,
+    pub pause: PauseState,
+    /// default_limit is the page size of paginated queries when none is given.
+    pub default_limit: u32,
+    /// max_limit is the largest page size paginated queries return.
+    pub max_limit: u32,
+    /// proposal_ttl is how long joint withdrawal proposals stay open, in seconds.
+    pub proposal_ttl: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    /// contract is the cw2 contract name.
+    pub contract: String,
+    /// version is the cw2 contract version.
+    pub version: String,
+    /// features lists the capabilities of the code, such as "escrow" or "claim_tokens", so
+    /// tooling can tell deployments apart.
+    pub features: Vec<String>,
+    pub instantiated_height: u64,
+    pub instantiated_time: Timestamp,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PotResponse {
     /// target_addr is the address that will receive the pot
     pub target_addr: String,
     /// collected keeps information on how much is collected for this pot.
     pub collected: String,
+    pub asset: String,
+    /// has_history tells a fully withdrawn pot apart from one that never received funds.
+    pub has_history: bool,
+    /// locked is held in open escrows for this address and not withdrawable yet.
+    pub locked: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PotsResponse {
+    pub pots: Vec<PotResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AccountResponse {
+    pub addr: String,
+    pub asset: String,
+    /// balance is what addr can withdraw right now.
+    pub balance: Uint128,
+    /// locked is held in open escrows for addr and not withdrawable yet.
+    pub locked: Uint128,
+    /// received is everything ever credited to addr.
+    pub received: Uint128,
+    /// withdrawn is everything ever paid out of addr's pot.
+    pub withdrawn: Uint128,
+    /// transferred is everything ever moved from addr's pot to another pot.
+    pub transferred: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct EscrowResponse {
+    pub id: u64,
+    pub depositor: String,
+    pub arbiter: String,
+    pub recipients: Vec<String>,
+    pub asset: String,
+    /// share is what each recipient is credited on release.
+    pub share: Uint128,
+    pub timeout: Option<Expiration>,
+    pub status: EscrowStatus,
+    /// released is what each recipient has been credited so far.
+    pub released: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestoneInfo {
+    pub index: usize,
+    pub description: String,
+    pub share: Decimal,
+    /// amount is what the milestone releases to each recipient.
+    pub amount: Uint128,
+    pub completed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MilestonesResponse {
+    pub milestones: Vec<MilestoneInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ForwardResponse {
+    /// forward is the confirmed forwarding address, if any.
+    pub forward: Option<String>,
+    /// pending is the forwarding address waiting for confirmation, if any.
+    pub pending: Option<String>,
+    /// resolved is where credits to addr end up once every forwarding is followed.
+    pub resolved: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitTemplateInfo {
+    pub id: u64,
+    pub name: String,
+    pub recipients: Vec<String>,
+    pub weights: Vec<u64>,
+    pub immutable: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitTemplatesResponse {
+    pub templates: Vec<SplitTemplateInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CampaignResponse {
+    pub id: u64,
+    pub creator: String,
+    pub recipients: Vec<String>,
+    pub asset: String,
+    pub goal: Uint128,
+    pub deadline: Expiration,
+    pub raised: Uint128,
+    pub refunded: Uint128,
+    pub contributors: u64,
+    /// progress is raised divided by goal, capped at the largest Decimal.
+    pub progress: Decimal,
+    pub status: CampaignStatus,
+    /// failed is set once the deadline passed without reaching the goal.
+    pub failed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContributionResponse {
+    /// amount is what is left to refund, 0 once refunded.
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimTokenResponse {
+    pub asset: String,
+    /// token is None until CreateClaimToken has completed for asset.
+    pub token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JointPotResponse {
+    pub members: Vec<String>,
+    pub asset: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JointProposalResponse {
+    pub id: u64,
+    pub proposer: String,
+    pub partner: String,
+    pub asset: String,
+    pub amount: Uint128,
+    pub recipient: String,
+    pub expires: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RolesResponse {
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoleMembersResponse {
+    pub members: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositInfo {
+    pub id: u64,
+    pub depositor: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub asset: String,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DepositsResponse {
+    pub deposits: Vec<DepositInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatementEntry {
+    pub id: u64,
+    pub kind: LedgerKind,
+    pub counterparty: String,
+    pub amount: Uint128,
+    pub balance: Uint128,
+    pub asset: String,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatementResponse {
+    pub entries: Vec<StatementEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetSolvency {
+    pub asset: String,
+    /// liabilities is the total owed to beneficiaries in this asset.
+    pub liabilities: Uint128,
+    /// balance is what the contract actually holds in this asset.
+    pub balance: Uint128,
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolvencyResponse {
+    pub assets: Vec<AssetSolvency>,
+    /// solvent is false as soon as any asset is under-collateralised.
+    pub solvent: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AssetStatsResponse {
+    pub asset: String,
+    pub deposited: Uint128,
+    pub withdrawn: Uint128,
+    pub deposit_count: u64,
+    pub beneficiaries: u64,
+    /// liabilities is what is currently owed to beneficiaries.
+    pub liabilities: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StatsResponse {
+    pub assets: Vec<AssetStatsResponse>,
 }
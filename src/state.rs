@@ -1,15 +1,93 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use cosmwasm_std::{Addr, DepsMut, StdResult, Response, Uint128, Uint64};
-use cw_storage_plus::{Item,Map};
+use cosmwasm_std::{Addr, BlockInfo, Decimal, Empty, StdResult, Storage, Timestamp, Uint128};
+use cw20::{AllowanceResponse, Denom, Expiration};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// owner is one of the addresses holding the Owner role, kept current when it is revoked.
     pub owner: Addr,
+    /// native_denoms lists the bank denoms CreatePot accepts.
+    pub native_denoms: Vec<String>,
+    /// cw20_tokens lists the cw20 contracts CreatePot accepts.
+    pub cw20_tokens: Vec<Addr>,
+    /// primary_asset is the asset used when a message leaves it out. It is fixed at
+    /// instantiation to the first native denom, or else the first cw20 token, and stays accepted.
+    pub primary_asset: Denom,
 }
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// Instantiation records the block the contract was instantiated in.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Instantiation {
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+pub const INSTANTIATION: Item<Instantiation> = Item::new("instantiation");
+
+/// ASSETS remembers every asset ever accepted, keyed by asset_key, so balances held in an
+/// asset that is no longer accepted can still be paid out.
+pub const ASSETS: Map<&str, Denom> = Map::new("assets");
+
+/// asset_key is how an asset is identified in storage and responses: the denom of a native
+/// coin or the address of a cw20 contract.
+pub fn asset_key(asset: &Denom) -> String {
+    match asset {
+        Denom::Native(denom) => denom.clone(),
+        Denom::Cw20(addr) => addr.to_string(),
+    }
+}
+
+/// Role is a privilege that can be granted to any number of addresses.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    /// Owner can grant and revoke roles and do everything the other roles can.
+    Owner,
+    /// FeeManager is meant to collect fees once the contract charges them.
+    FeeManager,
+    /// Pauser can freeze and unfreeze deposits and withdrawals.
+    Pauser,
+    /// Operator runs day to day administration.
+    Operator,
+}
+
+impl Role {
+    pub const ALL: [Role; 4] = [Role::Owner, Role::FeeManager, Role::Pauser, Role::Operator];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Owner => "owner",
+            Role::FeeManager => "fee_manager",
+            Role::Pauser => "pauser",
+            Role::Operator => "operator",
+        }
+    }
+}
+
+/// ROLES holds every granted role, keyed by (role, address).
+pub const ROLES: Map<(&str, &str), Empty> = Map::new("roles");
+
+pub fn has_role(storage: &dyn Storage, addr: &Addr, role: Role) -> bool {
+    ROLES.has(storage, (role.as_str(), addr.as_str()))
+}
+
+/// PauseState records which operations are currently frozen and who froze them.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PauseState {
+    /// deposits blocks CreatePot when set.
+    pub deposits: bool,
+    /// withdrawals blocks WithdrawPot, WithdrawFrom and allowance changes when set.
+    pub withdrawals: bool,
+    /// set_by is the address that last changed the pause state.
+    pub set_by: Option<Addr>,
+}
+
+pub const PAUSE: Item<PauseState> = Item::new("pause");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Pot {
     /// target_addr is the address that will receive the pot
@@ -18,11 +96,457 @@ pub struct Pot {
     pub collected: Uint128,
 }
 
-/// POT_SEQ holds the last pot ID
-pub const POTS: Map<&str, Pot> = Map::new("pot");
+/// POTS holds every pot, keyed by (target address, asset).
+pub const POTS: Map<(&str, &str), Pot> = Map::new("pot");
+
+/// POT_HOLDERS indexes POTS by (asset, target address) so the pots of one asset can be listed.
+pub const POT_HOLDERS: Map<(&str, &str), Empty> = Map::new("pot_holders");
+
+/// POT_TOTALS holds, per asset, the sum of all pot balances.
+pub const POT_TOTALS: Map<&str, Uint128> = Map::new("pot_totals");
+
+/// WITHDRAW_ALLOWANCES holds how much a spender may withdraw from an owner's pot,
+/// keyed by (owner, spender, asset).
+pub const WITHDRAW_ALLOWANCES: Map<(&str, &str, &str), AllowanceResponse> =
+    Map::new("withdraw_allowance");
+
+/// Deposit records one share of a CreatePot credited to a beneficiary.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Deposit {
+    /// depositor is the address that sent the tokens.
+    pub depositor: Addr,
+    /// recipient is the beneficiary credited with this share.
+    pub recipient: Addr,
+    /// amount is the share credited to recipient.
+    pub amount: Uint128,
+    /// asset is the token the deposit was made in.
+    pub asset: String,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// DEPOSIT_SEQ holds the last deposit ID
+pub const DEPOSIT_SEQ: Item<u64> = Item::new("deposit_seq");
+
+pub struct DepositIndexes<'a> {
+    pub depositor: MultiIndex<'a, String, Deposit, u64>,
+    pub recipient: MultiIndex<'a, String, Deposit, u64>,
+}
+
+impl<'a> IndexList<Deposit> for DepositIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Deposit>> + '_> {
+        let v: Vec<&dyn Index<Deposit>> = vec![&self.depositor, &self.recipient];
+        Box::new(v.into_iter())
+    }
+}
+
+/// deposits is the append-only deposit log, keyed by deposit ID.
+pub fn deposits<'a>() -> IndexedMap<'a, u64, Deposit, DepositIndexes<'a>> {
+    let indexes = DepositIndexes {
+        depositor: MultiIndex::new(
+            |d: &Deposit| d.depositor.to_string(),
+            "deposits",
+            "deposits__depositor",
+        ),
+        recipient: MultiIndex::new(
+            |d: &Deposit| d.recipient.to_string(),
+            "deposits",
+            "deposits__recipient",
+        ),
+    };
+    IndexedMap::new("deposits", indexes)
+}
+
+pub fn save_deposit(storage: &mut dyn Storage, deposit: &Deposit) -> StdResult<u64> {
+    let id = DEPOSIT_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    DEPOSIT_SEQ.save(storage, &id)?;
+    deposits().save(storage, id, deposit)?;
+    Ok(id)
+}
+
+/// LIABILITIES holds, per asset, the total owed to beneficiaries across all pots.
+pub const LIABILITIES: Map<&str, Uint128> = Map::new("liabilities");
+
+pub fn add_liability(storage: &mut dyn Storage, asset: &str, amount: Uint128) -> StdResult<Uint128> {
+    LIABILITIES.update(storage, asset, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_add(amount)?)
+    })
+}
+
+pub fn sub_liability(storage: &mut dyn Storage, asset: &str, amount: Uint128) -> StdResult<Uint128> {
+    LIABILITIES.update(storage, asset, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_sub(amount)?)
+    })
+}
+
+/// AccountTotals keeps lifetime totals for one beneficiary.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct AccountTotals {
+    /// received is everything ever credited to the account.
+    pub received: Uint128,
+    /// withdrawn is everything ever paid out of the account.
+    pub withdrawn: Uint128,
+    /// transferred is everything ever moved to another pot inside the contract.
+    #[serde(default)]
+    pub transferred: Uint128,
+}
+
+/// ACCOUNTS holds the totals of every beneficiary, keyed by (address, asset).
+pub const ACCOUNTS: Map<(&str, &str), AccountTotals> = Map::new("accounts");
+
+/// AssetStats keeps lifetime counters for one asset.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct AssetStats {
+    /// deposited is the total received through CreatePot.
+    pub deposited: Uint128,
+    /// withdrawn is the total paid out of pots.
+    pub withdrawn: Uint128,
+    /// deposit_count is the number of CreatePot calls.
+    pub deposit_count: u64,
+    /// beneficiaries is the number of distinct addresses ever credited by a deposit.
+    pub beneficiaries: u64,
+}
+
+pub const STATS: Map<&str, AssetStats> = Map::new("stats");
+
+/// BENEFICIARIES remembers every address credited per asset, keyed by (asset, address).
+pub const BENEFICIARIES: Map<(&str, &str), Empty> = Map::new("beneficiaries");
+
+/// LedgerKind says why an account balance moved.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerKind {
+    /// Deposit credits a share of a CreatePot.
+    Deposit,
+    /// Withdrawal debits tokens paid out of the pot.
+    Withdrawal,
+    /// FailedWithdrawal credits back a withdrawal whose transfer was rejected by the recipient.
+    FailedWithdrawal,
+    /// Refund credits the depositor of an escrow that was refunded.
+    Refund,
+    /// TransferOut debits a balance moved to another pot.
+    TransferOut,
+    /// TransferIn credits a balance moved from another pot.
+    TransferIn,
+    /// Redemption credits claim tokens sent back to the contract; the counterparty is the token.
+    Redemption,
+}
+
+/// LedgerEntry is one line of an account statement.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LedgerEntry {
+    pub kind: LedgerKind,
+    /// counterparty is the depositor for credits and the recipient for debits.
+    pub counterparty: Addr,
+    pub amount: Uint128,
+    /// balance is the pot balance right after this entry, or the joint pot balance for entries
+    /// recorded by a joint pot.
+    pub balance: Uint128,
+    pub asset: String,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+/// LEDGER_SEQ holds the last ledger entry ID
+pub const LEDGER_SEQ: Item<u64> = Item::new("ledger_seq");
+
+/// LEDGER holds every balance movement, keyed by (account, entry ID).
+pub const LEDGER: Map<(&str, u64), LedgerEntry> = Map::new("ledger");
+
+pub fn save_ledger_entry(storage: &mut dyn Storage, addr: &Addr, entry: &LedgerEntry) -> StdResult<u64> {
+    let id = LEDGER_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    LEDGER_SEQ.save(storage, &id)?;
+    LEDGER.save(storage, (addr.as_str(), id), entry)?;
+    Ok(id)
+}
+
+/// EscrowStatus tracks whether an escrow still holds its funds.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EscrowStatus {
+    Locked,
+    Released,
+    Refunded,
+}
+
+/// Milestone is one tranche of an escrow, unlocked when the arbiter completes it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Milestone {
+    pub description: String,
+    /// share is the part of each recipient's share this milestone unlocks.
+    pub share: Decimal,
+    pub completed: bool,
+}
+
+/// Escrow is a CreateEscrowPot deposit held until its arbiter releases or refunds it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Escrow {
+    pub depositor: Addr,
+    pub arbiter: Addr,
+    /// recipients are credited share each on release.
+    pub recipients: Vec<Addr>,
+    pub asset: String,
+    pub share: Uint128,
+    /// timeout lets the depositor refund without the arbiter once expired.
+    pub timeout: Option<Expiration>,
+    pub status: EscrowStatus,
+    /// milestones are completed in order, each releasing its tranche. Empty for a single release.
+    #[serde(default)]
+    pub milestones: Vec<Milestone>,
+    /// released is what each recipient has been credited so far.
+    #[serde(default)]
+    pub released: Uint128,
+}
+
+impl Escrow {
+    /// locked is what the escrow still holds across all recipients.
+    pub fn locked(&self) -> Uint128 {
+        (self.share - self.released) * Uint128::from(self.recipients.len() as u128)
+    }
+
+    /// tranche is what milestone index releases to each recipient. The last milestone takes
+    /// whatever rounding left over.
+    pub fn tranche(&self, index: usize) -> Uint128 {
+        if index + 1 == self.milestones.len() {
+            self.share - self.milestones[..index].iter().map(|m| self.share * m.share).sum::<Uint128>()
+        } else {
+            self.share * self.milestones[index].share
+        }
+    }
+}
+
+/// ESCROW_SEQ holds the last escrow ID
+pub const ESCROW_SEQ: Item<u64> = Item::new("escrow_seq");
+
+pub const ESCROWS: Map<u64, Escrow> = Map::new("escrows");
+
+/// LOCKED holds what open escrows will credit each recipient, keyed by (address, asset).
+/// Locked funds are part of the liabilities but cannot be withdrawn.
+pub const LOCKED: Map<(&str, &str), Uint128> = Map::new("locked");
+
+/// SplitTemplate is a saved list of recipients with their weights, reusable by CreatePot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SplitTemplate {
+    pub owner: Addr,
+    pub name: String,
+    pub recipients: Vec<Addr>,
+    /// weights[i] is the relative share of recipients[i].
+    pub weights: Vec<u64>,
+    /// immutable templates can be neither changed nor deleted.
+    pub immutable: bool,
+}
+
+/// TEMPLATE_SEQ holds the last split template ID
+pub const TEMPLATE_SEQ: Item<u64> = Item::new("template_seq");
+
+pub const TEMPLATES: Map<u64, SplitTemplate> = Map::new("templates");
+
+/// TEMPLATE_NAMES maps (owner, name) to the template ID.
+pub const TEMPLATE_NAMES: Map<(&str, &str), u64> = Map::new("template_names");
+
+/// CampaignStatus is Open while contributions are taken and Funded once the goal was paid out.
+/// A campaign still Open after its deadline without reaching its goal has failed.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignStatus {
+    Open,
+    Funded,
+}
+
+/// Campaign collects contributions towards goal, split between recipients once reached.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Campaign {
+    pub creator: Addr,
+    pub recipients: Vec<Addr>,
+    pub asset: String,
+    pub goal: Uint128,
+    pub deadline: Expiration,
+    /// raised is the total contributed, refunds included.
+    pub raised: Uint128,
+    /// refunded is the total refunded after the campaign failed.
+    pub refunded: Uint128,
+    pub contributors: u64,
+    pub status: CampaignStatus,
+}
+
+impl Campaign {
+    pub fn failed(&self, block: &BlockInfo) -> bool {
+        self.status == CampaignStatus::Open && self.raised < self.goal && self.deadline.is_expired(block)
+    }
+}
+
+/// CAMPAIGN_SEQ holds the last campaign ID
+pub const CAMPAIGN_SEQ: Item<u64> = Item::new("campaign_seq");
+
+pub const CAMPAIGNS: Map<u64, Campaign> = Map::new("campaigns");
+
+/// CONTRIBUTIONS holds what each contributor put into a campaign, keyed by (campaign ID, address).
+/// Entries are removed once refunded.
+pub const CONTRIBUTIONS: Map<(u64, &str), Uint128> = Map::new("contributions");
+
+/// TokenView is the asset shown through the cw20 queries, with the metadata TokenInfo reports.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenView {
+    pub asset: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// DEFAULT_TOKEN_DECIMALS is reported for the primary asset until a TokenView is set.
+pub const DEFAULT_TOKEN_DECIMALS: u8 = 6;
+
+/// TOKEN_VIEW holds the asset used by the cw20 queries. Unset means the primary asset.
+pub const TOKEN_VIEW: Item<TokenView> = Item::new("token_view");
+
+/// CLAIM_TOKENS holds the claim token contract of each asset that has one.
+pub const CLAIM_TOKENS: Map<&str, Addr> = Map::new("claim_tokens");
+
+/// CLAIM_TOKEN_ASSETS maps a claim token contract back to its asset.
+pub const CLAIM_TOKEN_ASSETS: Map<&str, String> = Map::new("claim_token_assets");
+
+/// PENDING_CLAIM_TOKEN holds the asset whose claim token is being instantiated, until the reply.
+pub const PENDING_CLAIM_TOKEN: Item<String> = Item::new("pending_claim_token");
+
+/// DEFAULT_PROPOSAL_TTL is how long joint withdrawal proposals stay open until changed, in seconds.
+pub const DEFAULT_PROPOSAL_TTL: u64 = 24 * 60 * 60;
+
+/// PROPOSAL_TTL holds how long joint withdrawal proposals stay open, in seconds.
+pub const PROPOSAL_TTL: Item<u64> = Item::new("proposal_ttl");
+
+/// JOINT_POTS holds the balances shared by two addresses, keyed by (member, member, asset) with
+/// the members in the order returned by joint_members.
+pub const JOINT_POTS: Map<(&str, &str, &str), Uint128> = Map::new("joint_pots");
+
+/// joint_members orders the two members of a joint pot so either can look it up.
+pub fn joint_members<'a>(a: &'a Addr, b: &'a Addr) -> (&'a Addr, &'a Addr) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// JointProposal is a withdrawal from a joint pot waiting for the other member to confirm it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JointProposal {
+    pub proposer: Addr,
+    pub partner: Addr,
+    pub asset: String,
+    pub amount: Uint128,
+    pub recipient: Addr,
+    pub expires: Timestamp,
+}
+
+/// PROPOSAL_SEQ holds the last joint proposal ID
+pub const PROPOSAL_SEQ: Item<u64> = Item::new("proposal_seq");
+
+pub const JOINT_PROPOSALS: Map<u64, JointProposal> = Map::new("joint_proposals");
+
+/// PendingWithdrawal is a withdrawal to a contract recipient whose transfer has not settled yet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingWithdrawal {
+    pub owner: Addr,
+    pub recipient: Addr,
+    /// spender is set when the withdrawal used an allowance.
+    pub spender: Option<Addr>,
+    pub asset: String,
+    pub amount: Uint128,
+}
+
+/// PENDING_WITHDRAWAL holds the withdrawal sent to a contract until its reply clears it. Transfers
+/// never call back into this contract and each execution sends at most one, so a single slot is
+/// enough for the reply to find it.
+pub const PENDING_WITHDRAWAL: Item<PendingWithdrawal> = Item::new("pending_withdrawal");
+
+/// AcceptPolicy says which deposits an account takes through CreatePot.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcceptPolicy {
+    AcceptAll {},
+    /// Allowlist only accepts deposits from the listed depositors.
+    Allowlist { depositors: Vec<String> },
+    RejectAll {},
+}
+
+impl Default for AcceptPolicy {
+    fn default() -> Self {
+        AcceptPolicy::AcceptAll {}
+    }
+}
+
+impl AcceptPolicy {
+    pub fn accepts(&self, depositor: &Addr) -> bool {
+        match self {
+            AcceptPolicy::AcceptAll {} => true,
+            AcceptPolicy::Allowlist { depositors } => depositors.iter().any(|d| d == depositor.as_str()),
+            AcceptPolicy::RejectAll {} => false,
+        }
+    }
+}
+
+/// RejectFallback is what CreatePot does with a share its recipient does not accept.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectFallback {
+    /// Fail rejects the whole deposit.
+    #[default]
+    Fail,
+    /// ReturnToDepositor credits the share to the depositor's pot instead.
+    ReturnToDepositor,
+}
+
+/// ACCEPT_POLICIES holds the accept policy of accounts that set one; others accept all deposits.
+pub const ACCEPT_POLICIES: Map<&str, AcceptPolicy> = Map::new("accept_policies");
+
+/// PENDING_FORWARDS holds forwarding addresses waiting for the new address to confirm them.
+pub const PENDING_FORWARDS: Map<&str, Addr> = Map::new("pending_forwards");
+
+/// FORWARDS holds confirmed forwarding addresses. They never form a loop.
+pub const FORWARDS: Map<&str, Addr> = Map::new("forwards");
+
+/// resolve_forward follows the forwarding addresses of addr to the account that receives its credits.
+pub fn resolve_forward(storage: &dyn Storage, addr: &Addr) -> StdResult<Addr> {
+    let mut addr = addr.clone();
+    while let Some(next) = FORWARDS.may_load(storage, addr.as_str())? {
+        addr = next;
+    }
+    Ok(addr)
+}
+
+/// credit_pot adds amount to addr's pot of asset, creating it if needed, and returns the updated pot.
+pub fn credit_pot(storage: &mut dyn Storage, addr: &Addr, asset: &str, amount: Uint128) -> StdResult<Pot> {
+    let pot = POTS.update(storage, (addr.as_str(), asset), |pot| -> StdResult<_> {
+        let mut pot = pot.unwrap_or(Pot {
+            target_addr: addr.clone(),
+            collected: Uint128::zero(),
+        });
+        pot.collected = pot.collected.checked_add(amount)?;
+        Ok(pot)
+    })?;
+    POT_HOLDERS.save(storage, (asset, addr.as_str()), &Empty {})?;
+    POT_TOTALS.update(storage, asset, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_add(amount)?)
+    })?;
+    Ok(pot)
+}
 
-pub fn save_pot(deps: DepsMut, pot1: &Pot, pot2: &Pot,) -> StdResult<()> {
-    // save pot with id
-    POTS.save(deps.storage, pot1.target_addr.as_str(), pot1);
-    POTS.save(deps.storage, pot2.target_addr.as_str(), pot2)
+/// debit_pot takes amount out of addr's pot of asset, removing the pot once it is empty, and
+/// returns the updated pot. Callers check the balance first; overdrawing is an error.
+pub fn debit_pot(storage: &mut dyn Storage, addr: &Addr, asset: &str, amount: Uint128) -> StdResult<Pot> {
+    let mut pot = POTS.may_load(storage, (addr.as_str(), asset))?.unwrap_or(Pot {
+        target_addr: addr.clone(),
+        collected: Uint128::zero(),
+    });
+    pot.collected = pot.collected.checked_sub(amount)?;
+    if pot.collected.is_zero() {
+        POTS.remove(storage, (addr.as_str(), asset));
+        POT_HOLDERS.remove(storage, (asset, addr.as_str()));
+    } else {
+        POTS.save(storage, (addr.as_str(), asset), &pot)?;
+    }
+    POT_TOTALS.update(storage, asset, |total| -> StdResult<_> {
+        Ok(total.unwrap_or_default().checked_sub(amount)?)
+    })?;
+    Ok(pot)
 }